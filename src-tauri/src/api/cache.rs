@@ -0,0 +1,622 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use super::error::ApiError;
+
+/// 缓存最多保留的条目数
+const MAX_ENTRIES: usize = 500;
+
+/// 缓存最多占用的字节数（响应体总大小）
+const MAX_BYTES: usize = 32 * 1024 * 1024;
+
+/// 持久化文件名（位于应用缓存目录）
+const CACHE_FILE_NAME: &str = "api_cache.json";
+
+/// 缓存修改后延迟写盘的时间，期间的多次修改合并为一次写入
+const PERSIST_DELAY: Duration = Duration::from_secs(2);
+
+/// 单条缓存记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// 最后一次从服务器确认有效的时间（Unix 秒）
+    stored_at: u64,
+    /// 有效期（秒），过期后需要重新验证
+    ttl: u64,
+    /// LRU 计数，越大表示越近使用
+    #[serde(default)]
+    last_used: u64,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.stored_at) < self.ttl
+    }
+}
+
+/// 有界 LRU API 缓存
+///
+/// 过期条目不会立即删除：有 `ETag` / `Last-Modified` 时用于条件请求，
+/// 网络不可用时作为离线结果返回。超出条目数或总大小时按最近最少使用淘汰。
+#[derive(Default)]
+pub struct ApiCache {
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: usize,
+    counter: u64,
+    persist_path: Option<PathBuf>,
+    /// 有未写入磁盘的修改
+    dirty: bool,
+    /// 每次清空时递增，用于丢弃清空前生成的快照
+    generation: u64,
+}
+
+/// 缓存查找结果
+pub enum Lookup {
+    /// 未过期，可直接使用
+    Fresh(String),
+    /// 已过期，附带用于条件请求的验证信息
+    Stale {
+        body: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    Miss,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl ApiCache {
+    fn touch(&mut self, key: &str) {
+        self.counter += 1;
+        let counter = self.counter;
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.last_used = counter;
+        }
+    }
+
+    /// 查找缓存
+    pub fn lookup(&mut self, key: &str) -> Lookup {
+        let now = now_secs();
+        self.touch(key);
+        match self.entries.get(key) {
+            Some(entry) if entry.is_fresh(now) => Lookup::Fresh(entry.body.clone()),
+            Some(entry) => Lookup::Stale {
+                body: entry.body.clone(),
+                etag: entry.etag.clone(),
+                last_modified: entry.last_modified.clone(),
+            },
+            None => Lookup::Miss,
+        }
+    }
+
+    /// 写入缓存条目
+    pub fn insert(&mut self, key: &str, body: String, etag: Option<String>, last_modified: Option<String>, ttl: Duration) {
+        if body.len() > MAX_BYTES {
+            tracing::debug!("[缓存跳过] {} 响应体过大 ({} bytes)", key, body.len());
+            return;
+        }
+        self.remove(key);
+        self.counter += 1;
+        self.total_bytes += body.len();
+        self.entries.insert(key.to_string(), CacheEntry {
+            body,
+            etag,
+            last_modified,
+            stored_at: now_secs(),
+            ttl: ttl.as_secs(),
+            last_used: self.counter,
+        });
+        self.dirty = true;
+        self.evict();
+    }
+
+    /// 服务器返回 304 时刷新条目的有效期
+    pub fn revalidate(&mut self, key: &str, ttl: Duration) {
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.stored_at = now_secs();
+            entry.ttl = ttl.as_secs();
+            self.dirty = true;
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(old) = self.entries.remove(key) {
+            self.total_bytes -= old.body.len();
+            self.dirty = true;
+        }
+    }
+
+    /// 按 LRU 淘汰直到满足条目数和大小限制
+    fn evict(&mut self) {
+        while self.entries.len() > MAX_ENTRIES || self.total_bytes > MAX_BYTES {
+            let oldest = self.entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(key) => {
+                    tracing::debug!("[缓存淘汰] {}", key);
+                    self.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 清空缓存并删除持久化文件，返回清除的条目数
+    ///
+    /// 文件在持有锁时删除，写盘线程不会在清空后用旧快照重新创建它。
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        self.total_bytes = 0;
+        self.dirty = false;
+        self.generation += 1;
+        if let Some(path) = self.persist_path.as_ref().filter(|p| p.exists()) {
+            if let Err(e) = std::fs::remove_file(path) {
+                tracing::warn!("删除 API 缓存文件失败: {}", e);
+            }
+        }
+        count
+    }
+
+    /// 有未保存的修改时序列化当前缓存，返回持久化路径、内容和生成快照时的代数
+    fn take_snapshot(&mut self) -> Option<(PathBuf, String, u64)> {
+        if !self.dirty {
+            return None;
+        }
+        let path = self.persist_path.clone()?;
+        self.dirty = false;
+        match serde_json::to_string(&self.entries) {
+            Ok(json) => Some((path, json, self.generation)),
+            Err(e) => {
+                tracing::warn!("序列化 API 缓存失败: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 用已写好的临时文件替换持久化文件（需持有缓存锁调用）
+    ///
+    /// 快照生成后缓存被清空时丢弃临时文件，不会恢复已清除的内容。
+    fn commit_snapshot(&self, temp_path: &Path, path: &Path, generation: u64) {
+        if self.generation != generation {
+            tracing::debug!("API 缓存已清空，丢弃旧快照");
+            let _ = std::fs::remove_file(temp_path);
+            return;
+        }
+        if let Err(e) = std::fs::rename(temp_path, path) {
+            tracing::warn!("保存 API 缓存失败: {}", e);
+        }
+    }
+}
+
+/// 全局 API 缓存
+static API_CACHE: OnceLock<Mutex<ApiCache>> = OnceLock::new();
+
+pub fn get_cache() -> &'static Mutex<ApiCache> {
+    API_CACHE.get_or_init(|| Mutex::new(ApiCache::default()))
+}
+
+fn lock_cache() -> Result<std::sync::MutexGuard<'static, ApiCache>, ApiError> {
+    get_cache()
+        .lock()
+        .map_err(|e| ApiError::Config(format!("锁定缓存失败: {}", e)))
+}
+
+/// 启用磁盘持久化并加载上次保存的缓存
+///
+/// 在应用启动时调用；未调用时缓存只保存在内存中。
+pub fn init_persistence(cache_dir: PathBuf) {
+    if let Err(e) = std::fs::create_dir_all(&cache_dir) {
+        tracing::warn!("创建缓存目录失败，API 缓存仅保存在内存中: {}", e);
+        return;
+    }
+    let path = cache_dir.join(CACHE_FILE_NAME);
+
+    let loaded: HashMap<String, CacheEntry> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    let Ok(mut cache) = lock_cache() else {
+        return;
+    };
+    cache.persist_path = Some(path.clone());
+    for (key, entry) in loaded {
+        cache.counter = cache.counter.max(entry.last_used);
+        cache.total_bytes += entry.body.len();
+        cache.entries.insert(key, entry);
+    }
+    cache.evict();
+    cache.dirty = false;
+    tracing::info!("API 缓存持久化已启用: {:?}（已加载 {} 条记录）", path, cache.len());
+    drop(cache);
+
+    let (sender, receiver) = mpsc::channel();
+    if PERSIST_SENDER.set(sender).is_ok() {
+        std::thread::spawn(move || persist_loop(receiver));
+    }
+}
+
+/// 通知写盘线程缓存有修改（未启用持久化时为空）
+static PERSIST_SENDER: OnceLock<Sender<()>> = OnceLock::new();
+
+/// 安排一次写盘，由写盘线程延迟合并执行
+fn persist() {
+    if let Some(sender) = PERSIST_SENDER.get() {
+        let _ = sender.send(());
+    }
+}
+
+/// 写盘线程：收到通知后等待一段时间再写入最新的快照，保证只有一个写入者
+fn persist_loop(receiver: Receiver<()>) {
+    while receiver.recv().is_ok() {
+        std::thread::sleep(PERSIST_DELAY);
+        while receiver.try_recv().is_ok() {}
+        flush();
+    }
+}
+
+/// 将缓存写入磁盘（序列化在锁内，IO 在锁外执行）
+fn flush() {
+    let Some((path, json, generation)) = lock_cache().ok().and_then(|mut cache| cache.take_snapshot()) else {
+        return;
+    };
+    let temp_path = path.with_extension("json.tmp");
+    if let Err(e) = std::fs::write(&temp_path, json) {
+        tracing::warn!("保存 API 缓存失败: {}", e);
+        return;
+    }
+
+    // 替换时持有缓存锁：清空缓存与替换文件不会交错，清空前的快照直接丢弃
+    if let Ok(cache) = lock_cache() {
+        cache.commit_snapshot(&temp_path, &path, generation);
+    }
+}
+
+/// 删除单条缓存（例如响应体无法解析时）
+pub fn invalidate(key: &str) {
+    if let Ok(mut cache) = lock_cache() {
+        cache.remove(key);
+    }
+    persist();
+}
+
+/// 读取本地计算的缓存值（无服务器验证信息，过期即失效）
+pub fn get_local(key: &str) -> Option<String> {
+    match lock_cache().ok()?.lookup(key) {
+        Lookup::Fresh(body) => Some(body),
+        _ => None,
+    }
+}
+
+/// 写入本地计算的缓存值
+pub fn put_local(key: &str, body: String, ttl: Duration) {
+    if let Ok(mut cache) = lock_cache() {
+        cache.insert(key, body, None, None, ttl);
+    }
+    persist();
+}
+
+/// 单个端点的缓存策略
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    /// 有效期，过期后发送条件请求重新验证
+    pub ttl: Duration,
+    /// 网络失败、服务器错误或被限流时是否返回过期缓存
+    pub offline_fallback: bool,
+}
+
+impl CachePolicy {
+    /// majdata.net 谱面列表
+    pub const CHART_LIST: CachePolicy = CachePolicy {
+        ttl: Duration::from_secs(10 * 60),
+        offline_fallback: true,
+    };
+
+    /// majdata.net 谱面详情
+    pub const CHART_DETAIL: CachePolicy = CachePolicy {
+        ttl: Duration::from_secs(30 * 60),
+        offline_fallback: true,
+    };
+
+    /// GitHub contents API（未认证 60 次/小时，304 响应不计入限额）
+    pub const GITHUB_CONTENTS: CachePolicy = CachePolicy {
+        ttl: Duration::from_secs(60 * 60),
+        offline_fallback: true,
+    };
+
+    /// 第三方皮肤 HTTP 索引
+    pub const SKIN_INDEX: CachePolicy = CachePolicy {
+        ttl: Duration::from_secs(30 * 60),
+        offline_fallback: true,
+    };
+
+    /// 远程哈希清单：更新判断依赖最新内容，有效期短且不回退到过期内容
+    pub const REMOTE_HASHES: CachePolicy = CachePolicy {
+        ttl: Duration::from_secs(60),
+        offline_fallback: false,
+    };
+}
+
+/// 是否属于可回退到过期缓存的错误状态（服务器错误、限流）
+fn is_fallback_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || status == reqwest::StatusCode::FORBIDDEN
+}
+
+/// 带缓存的 GET 请求，返回响应体文本
+///
+/// `request` 为已设置好请求头的 GET 请求，`key` 为缓存键（通常是 URL）。
+/// - 缓存未过期时直接返回，不发送请求
+/// - 过期且有 `ETag` / `Last-Modified` 时发送条件请求，304 则续期
+/// - 策略允许时，网络失败或服务器错误返回过期内容（离线浏览）
+pub async fn get_text_cached(request: reqwest::RequestBuilder, key: &str, policy: CachePolicy) -> Result<String, ApiError> {
+    let lookup = lock_cache()?.lookup(key);
+
+    let (stale_body, etag, last_modified) = match lookup {
+        Lookup::Fresh(body) => {
+            tracing::info!("[缓存命中] {}", key);
+            return Ok(body);
+        }
+        Lookup::Stale { body, etag, last_modified } => (Some(body), etag, last_modified),
+        Lookup::Miss => (None, None, None),
+    };
+    let fallback = stale_body.as_ref().filter(|_| policy.offline_fallback);
+
+    let mut request = request;
+    if let Some(etag) = &etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            if let Some(body) = fallback {
+                tracing::warn!("[离线缓存] 请求失败，返回过期缓存: {} ({})", key, e);
+                return Ok(body.clone());
+            }
+            return Err(e.into());
+        }
+    };
+
+    let status = response.status();
+
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(body) = stale_body {
+            tracing::info!("[缓存验证] {} 未修改", key);
+            if let Ok(mut cache) = lock_cache() {
+                cache.revalidate(key, policy.ttl);
+            }
+            persist();
+            return Ok(body);
+        }
+    }
+
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_else(|_| "无法读取错误响应体".to_string());
+        if is_fallback_status(status) {
+            if let Some(stale) = fallback {
+                tracing::warn!("[离线缓存] 服务器返回 {}，返回过期缓存: {}", status, key);
+                return Ok(stale.clone());
+            }
+        }
+        tracing::error!("❌ HTTP 请求失败!");
+        tracing::error!("  状态码: {}", status);
+        tracing::error!("  响应体: {}", super::error::preview_body(&body, 1000));
+        return Err(ApiError::Status { status: status.as_u16(), body });
+    }
+
+    let header = |name: reqwest::header::HeaderName| {
+        response.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    };
+    let new_etag = header(reqwest::header::ETAG);
+    let new_last_modified = header(reqwest::header::LAST_MODIFIED);
+
+    let body = response.text().await.map_err(|e| {
+        tracing::error!("❌ 读取响应体失败: {}", e);
+        ApiError::Network(e.to_string())
+    })?;
+
+    if let Ok(mut cache) = lock_cache() {
+        cache.insert(key, body.clone(), new_etag, new_last_modified, policy.ttl);
+        tracing::info!("[缓存存储] {} （当前缓存条数: {}）", key, cache.len());
+    }
+    persist();
+
+    Ok(body)
+}
+
+/// 带缓存的 GET 请求并解析 JSON，解析失败时丢弃对应的缓存条目
+pub async fn get_json_cached<T: DeserializeOwned>(request: reqwest::RequestBuilder, key: &str, policy: CachePolicy) -> Result<T, ApiError> {
+    let text = get_text_cached(request, key, policy).await?;
+    serde_json::from_str(&text).map_err(|e| {
+        tracing::error!("❌ 解析 JSON 失败!");
+        tracing::error!("  错误: {}", e);
+        tracing::error!("  响应体（前 1000 字符）: {}", super::error::preview_body(&text, 1000));
+        invalidate(key);
+        ApiError::Parse(e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn body(lookup: Lookup) -> Option<String> {
+        match lookup {
+            Lookup::Fresh(body) => Some(body),
+            _ => None,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("majdata-hub-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn fresh_within_ttl_stale_after() {
+        let mut cache = ApiCache::default();
+        cache.insert("fresh", "a".to_string(), None, None, TTL);
+        cache.insert("expired", "b".to_string(), Some("\"v1\"".to_string()), Some("Mon, 01 Jan 2024 00:00:00 GMT".to_string()), Duration::ZERO);
+
+        assert_eq!(body(cache.lookup("fresh")).as_deref(), Some("a"));
+        match cache.lookup("expired") {
+            Lookup::Stale { body, etag, last_modified } => {
+                assert_eq!(body, "b");
+                assert_eq!(etag.as_deref(), Some("\"v1\""));
+                assert_eq!(last_modified.as_deref(), Some("Mon, 01 Jan 2024 00:00:00 GMT"));
+            }
+            _ => panic!("过期条目应返回 Stale"),
+        }
+        assert!(matches!(cache.lookup("missing"), Lookup::Miss));
+    }
+
+    #[test]
+    fn revalidate_renews_stale_entry() {
+        let mut cache = ApiCache::default();
+        cache.insert("key", "body".to_string(), Some("\"v1\"".to_string()), None, Duration::ZERO);
+        assert!(matches!(cache.lookup("key"), Lookup::Stale { .. }));
+        cache.dirty = false;
+
+        cache.revalidate("key", TTL);
+        assert!(cache.dirty);
+        assert_eq!(body(cache.lookup("key")).as_deref(), Some("body"));
+
+        // 不存在的条目不受影响
+        cache.dirty = false;
+        cache.revalidate("missing", TTL);
+        assert!(!cache.dirty);
+        assert!(matches!(cache.lookup("missing"), Lookup::Miss));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_by_count() {
+        let mut cache = ApiCache::default();
+        for i in 0..MAX_ENTRIES {
+            cache.insert(&i.to_string(), "x".to_string(), None, None, TTL);
+        }
+        // 最早写入的 0 刚被使用，应淘汰 1
+        cache.lookup("0");
+        cache.insert("new", "x".to_string(), None, None, TTL);
+
+        assert_eq!(cache.len(), MAX_ENTRIES);
+        assert!(body(cache.lookup("0")).is_some());
+        assert!(matches!(cache.lookup("1"), Lookup::Miss));
+        assert!(body(cache.lookup("new")).is_some());
+    }
+
+    #[test]
+    fn evicts_least_recently_used_by_size() {
+        let mut cache = ApiCache::default();
+        let chunk = MAX_BYTES / 3;
+        cache.insert("a", "a".repeat(chunk), None, None, TTL);
+        cache.insert("b", "b".repeat(chunk), None, None, TTL);
+        cache.insert("c", "c".repeat(chunk), None, None, TTL);
+        cache.lookup("a");
+        cache.insert("d", "d".repeat(chunk), None, None, TTL);
+
+        assert_eq!(cache.len(), 3);
+        assert!(cache.total_bytes <= MAX_BYTES);
+        assert!(matches!(cache.lookup("b"), Lookup::Miss));
+        assert!(body(cache.lookup("a")).is_some());
+    }
+
+    #[test]
+    fn oversized_body_is_not_cached() {
+        let mut cache = ApiCache::default();
+        cache.insert("small", "x".to_string(), None, None, TTL);
+        cache.insert("huge", "x".repeat(MAX_BYTES + 1), None, None, TTL);
+        assert_eq!(cache.len(), 1);
+        assert!(matches!(cache.lookup("huge"), Lookup::Miss));
+    }
+
+    #[test]
+    fn replacing_entry_keeps_size_accounting() {
+        let mut cache = ApiCache::default();
+        cache.insert("key", "a".repeat(100), None, None, TTL);
+        cache.insert("key", "b".repeat(10), None, None, TTL);
+        assert_eq!(cache.total_bytes, 10);
+        cache.clear();
+        assert_eq!(cache.total_bytes, 0);
+    }
+
+    #[test]
+    fn snapshot_only_when_dirty() {
+        let dir = temp_dir("dirty");
+        let mut cache = ApiCache { persist_path: Some(dir.join(CACHE_FILE_NAME)), ..ApiCache::default() };
+        assert!(cache.take_snapshot().is_none());
+        cache.insert("key", "body".to_string(), None, None, TTL);
+        let (_, json, _) = cache.take_snapshot().unwrap();
+        assert!(json.contains("\"key\""));
+        assert!(cache.take_snapshot().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn snapshot_is_committed_when_not_cleared() {
+        let dir = temp_dir("commit");
+        let path = dir.join(CACHE_FILE_NAME);
+        let mut cache = ApiCache { persist_path: Some(path.clone()), ..ApiCache::default() };
+        cache.insert("key", "body".to_string(), None, None, TTL);
+
+        let (snapshot_path, json, generation) = cache.take_snapshot().unwrap();
+        let temp_path = snapshot_path.with_extension("json.tmp");
+        std::fs::write(&temp_path, json).unwrap();
+        cache.commit_snapshot(&temp_path, &snapshot_path, generation);
+
+        assert!(path.exists());
+        assert!(!temp_path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn snapshot_taken_before_clear_is_discarded() {
+        let dir = temp_dir("clear");
+        let path = dir.join(CACHE_FILE_NAME);
+        std::fs::write(&path, "{}").unwrap();
+        let mut cache = ApiCache { persist_path: Some(path.clone()), ..ApiCache::default() };
+        cache.insert("key", "body".to_string(), None, None, TTL);
+
+        // 写盘线程已生成快照并写好临时文件，此时缓存被清空
+        let (snapshot_path, json, generation) = cache.take_snapshot().unwrap();
+        let temp_path = snapshot_path.with_extension("json.tmp");
+        std::fs::write(&temp_path, json).unwrap();
+        assert_eq!(cache.clear(), 1);
+        assert!(!path.exists());
+
+        cache.commit_snapshot(&temp_path, &snapshot_path, generation);
+        assert!(!path.exists());
+        assert!(!temp_path.exists());
+        assert!(cache.take_snapshot().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
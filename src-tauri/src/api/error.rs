@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// 外部 API 调用的统一错误类型
+///
+/// Tauri 命令仍然返回 `Result<_, String>`，通过 `From<ApiError> for String` 在命令边界转换。
+#[derive(Debug)]
+pub enum ApiError {
+    /// 配置错误（无效的代理、基础 URL 等）
+    Config(String),
+    /// 网络请求失败（连接失败、超时等）
+    Network(String),
+    /// 服务器返回非 2xx 状态码
+    Status { status: u16, body: String },
+    /// 响应体解析失败
+    Parse(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Config(msg) => write!(f, "Invalid API configuration: {}", msg),
+            ApiError::Network(msg) => write!(f, "Network request failed: {}", msg),
+            ApiError::Status { status, body } => write!(f, "Request failed with status: {} - {}", status, body),
+            ApiError::Parse(msg) => write!(f, "Failed to parse response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ApiError> for String {
+    fn from(e: ApiError) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        tracing::error!("❌ 网络请求失败!");
+        tracing::error!("  错误类型: {}", e);
+        tracing::error!("  是否超时: {}", e.is_timeout());
+        tracing::error!("  是否连接错误: {}", e.is_connect());
+        ApiError::Network(e.to_string())
+    }
+}
+
+/// 截取响应体前若干字符用于日志（按字符截断，避免切断多字节字符）
+pub fn preview_body(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Ordering;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use super::majdata::ChartSummary;
+
+/// 谱面定数等级，如 "13"、"13+"
+///
+/// 比较顺序为 13 < 13+ < 14。小数定数（如 "13.7"）按小数部分归入对应等级：
+/// 小数部分 ≥ 0.7 视为 "+"。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChartLevel {
+    pub base: u32,
+    pub plus: bool,
+}
+
+impl ChartLevel {
+    /// 解析等级字符串，无法识别（空字符串、"?"、宴谱等）时返回 `None`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(base) = text.strip_suffix('+') {
+            return base.trim().parse().ok().map(|base| ChartLevel { base, plus: true });
+        }
+        if let Ok(base) = text.parse::<u32>() {
+            return Some(ChartLevel { base, plus: false });
+        }
+        // 按小数位判断，避免浮点误差（13.7 的 f32 小数部分略小于 0.7）
+        let (base, fraction) = text.split_once('.')?;
+        if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let base = base.parse().ok()?;
+        let plus = fraction.as_bytes()[0] >= b'7';
+        Some(ChartLevel { base, plus })
+    }
+}
+
+impl Ord for ChartLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.base, self.plus).cmp(&(other.base, other.plus))
+    }
+}
+
+impl PartialOrd for ChartLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for ChartLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.base, if self.plus { "+" } else { "" })
+    }
+}
+
+/// 谱面列表过滤条件
+///
+/// API 只支持自由文本搜索，等级、难度位置、谱师和上传者在解析后的列表上过滤。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChartFilter {
+    /// 最低等级（含），如 "13+"
+    #[serde(default)]
+    pub min_level: Option<String>,
+    /// 最高等级（含），如 "14+"
+    #[serde(default)]
+    pub max_level: Option<String>,
+    /// 只检查某个难度位置（0 = Easy … 4 = Master, 5 = Re:Master, 6 = 宴）
+    #[serde(default)]
+    pub difficulty: Option<usize>,
+    /// 谱师（不区分大小写的包含匹配）
+    #[serde(default)]
+    pub designer: Option<String>,
+    /// 上传者（不区分大小写的包含匹配）
+    #[serde(default)]
+    pub uploader: Option<String>,
+}
+
+/// 将空字符串视为未设置
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// 不区分大小写的包含匹配
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+impl ChartFilter {
+    /// 是否设置了任何过滤条件
+    pub fn is_empty(&self) -> bool {
+        non_empty(&self.min_level).is_none()
+            && non_empty(&self.max_level).is_none()
+            && self.difficulty.is_none()
+            && non_empty(&self.designer).is_none()
+            && non_empty(&self.uploader).is_none()
+    }
+
+    /// 可以交给服务器搜索的关键词
+    ///
+    /// 用户未输入搜索词时，用谱师或上传者缩小服务器返回的范围，剩余条件仍在本地过滤。
+    pub fn server_search(&self) -> Option<&str> {
+        non_empty(&self.designer).or_else(|| non_empty(&self.uploader))
+    }
+
+    /// 校验等级参数，返回解析后的 (最低, 最高) 等级
+    fn level_bounds(&self) -> Result<(Option<ChartLevel>, Option<ChartLevel>), String> {
+        let parse = |value: &Option<String>| -> Result<Option<ChartLevel>, String> {
+            match non_empty(value) {
+                Some(text) => ChartLevel::parse(text)
+                    .map(Some)
+                    .ok_or_else(|| format!("无效的等级: {}", text)),
+                None => Ok(None),
+            }
+        };
+        let min = parse(&self.min_level)?;
+        let max = parse(&self.max_level)?;
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(format!("最低等级 {} 高于最高等级 {}", min, max));
+            }
+        }
+        Ok((min, max))
+    }
+
+    /// 在解析后的谱面列表上应用过滤条件
+    pub fn apply(&self, charts: Vec<ChartSummary>) -> Result<Vec<ChartSummary>, String> {
+        if self.is_empty() {
+            return Ok(charts);
+        }
+        let (min, max) = self.level_bounds()?;
+        let level_active = min.is_some() || max.is_some() || self.difficulty.is_some();
+        let designer = non_empty(&self.designer);
+        let uploader = non_empty(&self.uploader);
+
+        let level_matches = |level: &Option<String>| -> bool {
+            let Some(level) = level.as_deref().and_then(ChartLevel::parse) else {
+                return false;
+            };
+            min.is_none_or(|min| level >= min) && max.is_none_or(|max| level <= max)
+        };
+
+        Ok(charts
+            .into_iter()
+            .filter(|chart| designer.is_none_or(|d| contains_ignore_case(&chart.designer, d)))
+            .filter(|chart| uploader.is_none_or(|u| contains_ignore_case(&chart.uploader, u)))
+            .filter(|chart| {
+                if !level_active {
+                    return true;
+                }
+                match self.difficulty {
+                    Some(slot) => chart.levels.get(slot).is_some_and(level_matches),
+                    None => chart.levels.iter().any(level_matches),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(base: u32, plus: bool) -> ChartLevel {
+        ChartLevel { base, plus }
+    }
+
+    fn chart(id: &str, designer: &str, uploader: &str, levels: &[Option<&str>]) -> ChartSummary {
+        ChartSummary {
+            id: id.to_string(),
+            title: id.to_string(),
+            artist: String::new(),
+            designer: designer.to_string(),
+            uploader: uploader.to_string(),
+            levels: levels.iter().map(|l| l.map(str::to_string)).collect(),
+        }
+    }
+
+    fn ids(charts: Vec<ChartSummary>) -> Vec<String> {
+        charts.into_iter().map(|c| c.id).collect()
+    }
+
+    #[test]
+    fn parses_plain_and_plus_levels() {
+        assert_eq!(ChartLevel::parse("13"), Some(level(13, false)));
+        assert_eq!(ChartLevel::parse("13+"), Some(level(13, true)));
+        assert_eq!(ChartLevel::parse(" 14 + "), Some(level(14, true)));
+    }
+
+    #[test]
+    fn parses_decimal_levels() {
+        assert_eq!(ChartLevel::parse("13.6"), Some(level(13, false)));
+        assert_eq!(ChartLevel::parse("13.69"), Some(level(13, false)));
+        assert_eq!(ChartLevel::parse("13.7"), Some(level(13, true)));
+        assert_eq!(ChartLevel::parse("13.9"), Some(level(13, true)));
+        assert_eq!(ChartLevel::parse("14.0"), Some(level(14, false)));
+    }
+
+    #[test]
+    fn rejects_unknown_levels() {
+        for text in ["", "?", "宴", "-1", "13.", "13.x", "NaN", "+"] {
+            assert_eq!(ChartLevel::parse(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn orders_plus_between_levels() {
+        assert!(level(13, false) < level(13, true));
+        assert!(level(13, true) < level(14, false));
+        assert_eq!(level(13, true).to_string(), "13+");
+    }
+
+    #[test]
+    fn empty_filter_keeps_everything() {
+        let charts = vec![chart("a", "x", "y", &[None]), chart("b", "x", "y", &[])];
+        assert_eq!(ids(ChartFilter::default().apply(charts).unwrap()), ["a", "b"]);
+    }
+
+    #[test]
+    fn filters_by_level_range_on_any_difficulty() {
+        let charts = vec![
+            chart("low", "", "", &[Some("5"), Some("12+")]),
+            chart("mid", "", "", &[Some("7"), Some("13+")]),
+            chart("high", "", "", &[Some("9"), Some("14.8")]),
+            chart("unknown", "", "", &[None, Some("?")]),
+        ];
+        let filter = ChartFilter {
+            min_level: Some("13+".to_string()),
+            max_level: Some("14".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(filter.apply(charts).unwrap()), ["mid"]);
+    }
+
+    #[test]
+    fn filters_by_difficulty_slot() {
+        let charts = vec![
+            chart("master", "", "", &[Some("5"), Some("7"), Some("9"), Some("12"), Some("14")]),
+            chart("expert", "", "", &[Some("5"), Some("7"), Some("14"), Some("12"), Some("13")]),
+        ];
+        let filter = ChartFilter {
+            min_level: Some("14".to_string()),
+            difficulty: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(ids(filter.apply(charts.clone()).unwrap()), ["master"]);
+
+        // 只指定难度位置时，该位置有可识别的等级即可
+        let filter = ChartFilter { difficulty: Some(5), ..Default::default() };
+        assert!(filter.apply(charts).unwrap().is_empty());
+    }
+
+    #[test]
+    fn filters_by_designer_and_uploader_ignoring_case() {
+        let charts = vec![
+            chart("a", "Alice", "Uploader1", &[]),
+            chart("b", "bob", "uploader2", &[]),
+        ];
+        let filter = ChartFilter { designer: Some("ALI".to_string()), ..Default::default() };
+        assert_eq!(ids(filter.apply(charts.clone()).unwrap()), ["a"]);
+        let filter = ChartFilter { uploader: Some("Uploader2".to_string()), ..Default::default() };
+        assert_eq!(ids(filter.apply(charts.clone()).unwrap()), ["b"]);
+        let filter = ChartFilter { designer: Some("  ".to_string()), ..Default::default() };
+        assert_eq!(filter.apply(charts).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_level_bounds() {
+        let filter = ChartFilter { min_level: Some("abc".to_string()), ..Default::default() };
+        assert!(filter.apply(Vec::new()).is_err());
+        let filter = ChartFilter {
+            min_level: Some("14".to_string()),
+            max_level: Some("13+".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.apply(Vec::new()).is_err());
+    }
+}
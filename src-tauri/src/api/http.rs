@@ -0,0 +1,105 @@
+use std::sync::RwLock;
+use std::time::Duration;
+use crate::settings::{redact_proxy, Settings};
+
+/// 所有请求使用的 User-Agent
+pub const USER_AGENT: &str = concat!("MajdataHub/", env!("CARGO_PKG_VERSION"));
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+/// 连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 空闲连接保留时间
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// TCP keep-alive 间隔
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// 影响客户端构建的设置项
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClientConfig {
+    proxy: Option<String>,
+    timeout_secs: u64,
+}
+
+impl ClientConfig {
+    fn from_settings(settings: &Settings) -> Self {
+        ClientConfig {
+            proxy: settings.proxy(),
+            timeout_secs: settings.request_timeout_secs(),
+        }
+    }
+}
+
+/// 创建 HTTP 客户端，支持代理和重定向
+///
+/// 启用连接保持和 HTTP/2（通过 ALPN 协商），所有请求使用统一的 User-Agent。
+pub fn create_http_client(proxy: Option<String>, timeout_secs: u64) -> Result<reqwest::Client, String> {
+    let mut client_builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(Duration::from_secs(timeout_secs))
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .http2_adaptive_window(true);
+
+    if let Some(proxy_url) = proxy {
+        if !proxy_url.is_empty() {
+            let proxy = reqwest::Proxy::all(&proxy_url)
+                .map_err(|e| {
+                    tracing::error!("无效的代理 URL: {}", e);
+                    format!("Invalid proxy URL: {}", e)
+                })?;
+            client_builder = client_builder.proxy(proxy);
+        }
+    }
+
+    client_builder.build()
+        .map_err(|e| {
+            tracing::error!("创建 HTTP 客户端失败: {}", e);
+            format!("Failed to create HTTP client: {}", e)
+        })
+}
+
+/// 共享的 HTTP 客户端（Tauri 托管状态）
+///
+/// 所有网络命令共用同一个客户端及其连接池，仅在代理或超时设置变化时重新创建。
+#[derive(Default)]
+pub struct HttpClient {
+    inner: RwLock<Option<(ClientConfig, reqwest::Client)>>,
+}
+
+impl HttpClient {
+    /// 获取与当前设置匹配的客户端
+    ///
+    /// `reqwest::Client` 内部使用 `Arc`，克隆开销很小且共享连接池。
+    pub fn get(&self, settings: &Settings) -> Result<reqwest::Client, String> {
+        let config = ClientConfig::from_settings(settings);
+        if let Ok(guard) = self.inner.read() {
+            if let Some((current, client)) = guard.as_ref() {
+                if *current == config {
+                    return Ok(client.clone());
+                }
+            }
+        }
+
+        let mut guard = self.inner.write().map_err(|e| format!("锁定 HTTP 客户端失败: {}", e))?;
+        // 其他线程可能已经按相同设置重建过
+        if let Some((current, client)) = guard.as_ref() {
+            if *current == config {
+                return Ok(client.clone());
+            }
+        }
+        tracing::info!(
+            "创建 HTTP 客户端，代理: {:?}，超时: {} 秒",
+            config.proxy.as_deref().map(redact_proxy),
+            config.timeout_secs
+        );
+        let client = create_http_client(config.proxy.clone(), config.timeout_secs)?;
+        *guard = Some((config, client.clone()));
+        Ok(client)
+    }
+}
@@ -0,0 +1,429 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use super::cache::{self, CachePolicy};
+use super::error::{preview_body, ApiError};
+use super::filter::ChartFilter;
+
+/// majdata.net 官方 API 根地址
+pub const DEFAULT_API_BASE: &str = "https://majdata.net/api3/api";
+
+/// 谱面列表排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartSort {
+    /// 上传日期（服务器默认）
+    #[default]
+    Date,
+    /// 点赞数
+    Likes,
+    /// 评论数
+    Comments,
+    /// 播放数
+    Plays,
+}
+
+impl ChartSort {
+    /// 对应 API 的 `sort` 参数值
+    pub fn as_query(self) -> &'static str {
+        match self {
+            ChartSort::Date => "",
+            ChartSort::Likes => "likep",
+            ChartSort::Comments => "commp",
+            ChartSort::Plays => "playp",
+        }
+    }
+}
+
+/// 谱面列表查询参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChartListQuery {
+    #[serde(default)]
+    pub search: String,
+    #[serde(default)]
+    pub sort: ChartSort,
+    #[serde(default)]
+    pub page: u32,
+    #[serde(default)]
+    pub filter: ChartFilter,
+}
+
+/// 谱面摘要信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartSummary {
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+    pub designer: String,
+    pub uploader: String,
+    pub levels: Vec<Option<String>>,  // API 返回数组，可能包含 null、空字符串或 "13+", "14" 等
+}
+
+/// 服务器每页返回的谱面数量
+pub const CHART_LIST_PAGE_SIZE: usize = 30;
+
+/// 谱面列表的一页
+#[derive(Debug, Clone, Serialize)]
+pub struct ChartListPage {
+    pub charts: Vec<ChartSummary>,
+    /// 服务器是否还有下一页；按过滤前的数量判断，过滤后的结果可能少于一页
+    pub has_more: bool,
+}
+
+/// 谱面各文件大小（字节），通过 HEAD 请求获取，服务器未返回时为 `None`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChartFileSizes {
+    pub track: Option<u64>,
+    pub image: Option<u64>,
+    pub chart: Option<u64>,
+    pub video: Option<u64>,
+}
+
+/// 谱面详细信息
+///
+/// 基本信息来自 `/maichart/{id}/summary`，缺少必需字段时解析失败而不是显示空值；
+/// 点赞/播放/评论数、各难度谱师和文件大小由 `fetch_chart_detail` 分别获取后填入。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartDetail {
+    #[serde(flatten)]
+    pub summary: ChartSummary,
+    #[serde(skip_deserializing)]
+    pub likes: u64,
+    #[serde(skip_deserializing)]
+    pub plays: u64,
+    #[serde(skip_deserializing)]
+    pub comments: u64,
+    /// 上传时间（Unix 秒）
+    #[serde(rename(deserialize = "timestamp"))]
+    pub upload_time: i64,
+    /// 未设置标签的谱面不返回该字段
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+    /// 每个难度的谱师，与 `levels` 一一对应
+    #[serde(skip_deserializing)]
+    pub designers: Vec<Option<String>>,
+    #[serde(skip_deserializing)]
+    pub file_sizes: ChartFileSizes,
+}
+
+/// 谱面互动信息（`/maichart/{id}/interact`）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartInteract {
+    /// 点赞的用户列表，只取数量
+    #[serde(deserialize_with = "count_items")]
+    pub like_list: u64,
+    pub plays: u64,
+    /// 评论列表，只取数量
+    #[serde(deserialize_with = "count_items")]
+    pub comments_list: u64,
+}
+
+/// 读取数组或对象的元素个数
+fn count_items<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Array(items) => Ok(items.len() as u64),
+        serde_json::Value::Object(items) => Ok(items.len() as u64),
+        other => Err(serde::de::Error::custom(format!("应为数组或对象，实际为 {}", other))),
+    }
+}
+
+/// 从 maidata.txt 中读取各难度谱师（`&des_1` ~ `&des_7`），未单独设置的难度使用 `&des`
+pub fn parse_designers(maidata: &str, count: usize) -> Vec<Option<String>> {
+    let mut common = None;
+    let mut designers = vec![None; count];
+    for line in maidata.lines() {
+        let Some((key, value)) = line.trim_start().strip_prefix('&').and_then(|l| l.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim().strip_prefix("des") {
+            Some("") => common = Some(value.to_string()),
+            Some(index) => {
+                let slot = index.strip_prefix('_')
+                    .and_then(|i| i.parse::<usize>().ok())
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| designers.get_mut(i));
+                if let Some(slot) = slot {
+                    *slot = Some(value.to_string());
+                }
+            }
+            None => {}
+        }
+    }
+    designers.into_iter().map(|d| d.or_else(|| common.clone())).collect()
+}
+
+/// majdata.net API 客户端
+///
+/// 基础 URL 可配置，用于镜像站；所有谱面相关命令都通过它构建 URL 和发送请求。
+pub struct MajdataClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl MajdataClient {
+    /// 创建客户端，`base_url` 为空时使用官方地址；`http` 为共享的 HTTP 客户端
+    pub fn new(base_url: Option<String>, http: reqwest::Client) -> Result<Self, ApiError> {
+        let base_url = match base_url {
+            Some(url) if !url.trim().is_empty() => {
+                let url = url.trim().trim_end_matches('/').to_string();
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(ApiError::Config(format!("API 地址必须以 http:// 或 https:// 开头: {}", url)));
+                }
+                url
+            }
+            _ => DEFAULT_API_BASE.to_string(),
+        };
+        Ok(Self { http, base_url })
+    }
+
+    /// 谱面列表 URL
+    ///
+    /// 搜索词为空时，使用过滤条件中的谱师/上传者作为服务器搜索词
+    pub fn chart_list_url(&self, query: &ChartListQuery) -> String {
+        let search = match query.search.trim() {
+            "" => query.filter.server_search().unwrap_or(""),
+            search => search,
+        };
+        format!(
+            "{}/maichart/list?sort={}&page={}&search={}",
+            self.base_url,
+            query.sort.as_query(),
+            query.page,
+            urlencoding::encode(search)
+        )
+    }
+
+    /// 谱面详情 URL
+    pub fn chart_detail_url(&self, chart_id: &str) -> String {
+        format!("{}/maichart/{}/summary", self.base_url, urlencoding::encode(chart_id))
+    }
+
+    /// 谱面互动信息（点赞/播放/评论）URL
+    pub fn chart_interact_url(&self, chart_id: &str) -> String {
+        format!("{}/maichart/{}/interact", self.base_url, urlencoding::encode(chart_id))
+    }
+
+    /// 谱面音频 URL
+    pub fn track_url(&self, chart_id: &str) -> String {
+        format!("{}/maichart/{}/track", self.base_url, urlencoding::encode(chart_id))
+    }
+
+    /// 谱面背景图 URL，`full_image` 为 false 时返回缩略图
+    pub fn image_url(&self, chart_id: &str, full_image: bool) -> String {
+        let url = format!("{}/maichart/{}/image", self.base_url, urlencoding::encode(chart_id));
+        if full_image {
+            format!("{}?fullImage=true", url)
+        } else {
+            url
+        }
+    }
+
+    /// 谱面文件（maidata.txt）URL
+    pub fn chart_url(&self, chart_id: &str) -> String {
+        format!("{}/maichart/{}/chart", self.base_url, urlencoding::encode(chart_id))
+    }
+
+    /// 谱面视频 URL
+    pub fn video_url(&self, chart_id: &str) -> String {
+        format!("{}/maichart/{}/video", self.base_url, urlencoding::encode(chart_id))
+    }
+
+    /// 发送 GET 请求
+    async fn get(&self, url: &str) -> Result<reqwest::Response, ApiError> {
+        self.send(self.http.get(url)).await
+    }
+
+    /// 发送请求，检查状态码
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, ApiError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_else(|_| "无法读取错误响应体".to_string());
+            tracing::error!("❌ HTTP 请求失败!");
+            tracing::error!("  状态码: {}", status);
+            tracing::error!("  响应体: {}", preview_body(&body, 1000));
+            return Err(ApiError::Status { status: status.as_u16(), body });
+        }
+        Ok(response)
+    }
+
+    /// 获取并解析 JSON，经过 API 缓存（支持过期重新验证和离线回退）
+    pub async fn get_json_cached<T: DeserializeOwned>(&self, url: &str, policy: CachePolicy) -> Result<T, ApiError> {
+        cache::get_json_cached(self.http.get(url), url, policy).await
+    }
+
+    /// 获取响应体字节和 Content-Type
+    pub async fn get_bytes(&self, url: &str) -> Result<(Vec<u8>, Option<String>), ApiError> {
+        let response = self.get(url).await?;
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let bytes = response.bytes().await.map_err(|e| {
+            tracing::error!("❌ 读取响应失败: {}", e);
+            ApiError::Network(e.to_string())
+        })?;
+        Ok((bytes.to_vec(), content_type))
+    }
+
+    /// 只获取响应体的前 `max_bytes` 字节
+    ///
+    /// 优先使用 Range 请求；服务器忽略 Range 返回完整内容时，读取到上限后停止接收。
+    pub async fn get_prefix(&self, url: &str, max_bytes: usize) -> Result<(Vec<u8>, Option<String>), ApiError> {
+        let request = self.http
+            .get(url)
+            .header(reqwest::header::RANGE, format!("bytes=0-{}", max_bytes.saturating_sub(1)));
+        let mut response = self.send(request).await?;
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            tracing::debug!("服务器不支持 Range 请求，使用流式截断: {}", url);
+        }
+
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        let mut bytes = Vec::with_capacity(max_bytes);
+        while bytes.len() < max_bytes {
+            let chunk = response.chunk().await.map_err(|e| {
+                tracing::error!("❌ 读取响应失败: {}", e);
+                ApiError::Network(e.to_string())
+            })?;
+            match chunk {
+                Some(chunk) => bytes.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+        bytes.truncate(max_bytes);
+        Ok((bytes, content_type))
+    }
+
+    /// 通过 HEAD 请求获取文件大小，失败或服务器未返回时为 `None`
+    pub async fn content_length(&self, url: &str) -> Option<u64> {
+        let response = self.http.head(url).send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse().ok())
+    }
+
+    /// 获取谱面各文件大小
+    pub async fn chart_file_sizes(&self, chart_id: &str) -> ChartFileSizes {
+        let track_url = self.track_url(chart_id);
+        let image_url = self.image_url(chart_id, true);
+        let chart_url = self.chart_url(chart_id);
+        let video_url = self.video_url(chart_id);
+        let (track, image, chart, video) = futures_util::join!(
+            self.content_length(&track_url),
+            self.content_length(&image_url),
+            self.content_length(&chart_url),
+            self.content_length(&video_url),
+        );
+        ChartFileSizes { track, image, chart, video }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn summary() -> serde_json::Value {
+        json!({
+            "id": "6c1e2a",
+            "title": "Test Song",
+            "artist": "Artist",
+            "designer": "Designer",
+            "uploader": "uploader",
+            "levels": ["", "", "11", "13+", "14", null, null],
+            "description": "desc",
+            "timestamp": 1717171717,
+            "hash": "abc",
+            "tags": ["original"]
+        })
+    }
+
+    #[test]
+    fn parses_summary() {
+        let detail: ChartDetail = serde_json::from_value(summary()).unwrap();
+        assert_eq!(detail.summary.title, "Test Song");
+        assert_eq!(detail.upload_time, 1717171717);
+        assert_eq!(detail.tags, vec!["original"]);
+        assert_eq!(detail.description.as_deref(), Some("desc"));
+        assert_eq!(detail.likes, 0);
+        assert!(detail.designers.is_empty());
+    }
+
+    #[test]
+    fn summary_without_timestamp_fails() {
+        let mut value = summary();
+        value.as_object_mut().unwrap().remove("timestamp");
+        assert!(serde_json::from_value::<ChartDetail>(value).is_err());
+    }
+
+    #[test]
+    fn summary_does_not_take_counters_from_response() {
+        let mut value = summary();
+        value["likes"] = json!(99);
+        let detail: ChartDetail = serde_json::from_value(value).unwrap();
+        assert_eq!(detail.likes, 0);
+        // 输出给前端的字段名不变
+        let output = serde_json::to_value(&detail).unwrap();
+        assert_eq!(output["upload_time"], 1717171717);
+        assert!(output.get("likes").is_some());
+    }
+
+    #[test]
+    fn parses_interact_counts() {
+        let interact: ChartInteract = serde_json::from_value(json!({
+            "isLiked": false,
+            "likeList": ["a", "b"],
+            "isDisLiked": false,
+            "disLikeList": [],
+            "commentsList": { "a": "nice", "b": "great", "c": "ok" },
+            "plays": 42
+        })).unwrap();
+        assert_eq!(interact.like_list, 2);
+        assert_eq!(interact.comments_list, 3);
+        assert_eq!(interact.plays, 42);
+
+        let interact: ChartInteract = serde_json::from_value(json!({
+            "likeList": [], "commentsList": [{ "user": "a" }], "plays": 0
+        })).unwrap();
+        assert_eq!(interact.comments_list, 1);
+    }
+
+    #[test]
+    fn interact_missing_counters_fails() {
+        assert!(serde_json::from_value::<ChartInteract>(json!({ "likeList": [], "commentsList": [] })).is_err());
+        assert!(serde_json::from_value::<ChartInteract>(json!({ "likes": 3, "commentsList": [], "plays": 1 })).is_err());
+        assert!(serde_json::from_value::<ChartInteract>(json!({ "likeList": 3, "commentsList": [], "plays": 1 })).is_err());
+    }
+
+    #[test]
+    fn parses_designers_per_level() {
+        let maidata = "&title=Test\n&des=Common\n&lv_4=13+\n&des_4=Expert\n&inote_4=(120){4}1,2,E\n&des_5= Master \n&des_6=\n&des_9=Out\n";
+        let designers = parse_designers(maidata, 7);
+        assert_eq!(designers.len(), 7);
+        assert_eq!(designers[0].as_deref(), Some("Common"));
+        assert_eq!(designers[3].as_deref(), Some("Expert"));
+        assert_eq!(designers[4].as_deref(), Some("Master"));
+        assert_eq!(designers[5].as_deref(), Some("Common"));
+    }
+
+    #[test]
+    fn designers_without_des_fields_are_none() {
+        assert_eq!(parse_designers("&title=Test\n&inote_5=1,E", 3), vec![None, None, None]);
+    }
+}
@@ -0,0 +1,12 @@
+// 模块声明
+pub mod cache;
+pub mod error;
+pub mod filter;
+pub mod http;
+pub mod majdata;
+pub mod skin_source;
+
+// 重新导出常用类型
+pub use http::HttpClient;
+pub use majdata::{ChartDetail, ChartFileSizes, ChartListPage, ChartListQuery, ChartSummary, MajdataClient};
+pub use skin_source::{GithubSkin, SkinSource};
@@ -0,0 +1,293 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use super::cache::{self, CachePolicy};
+use super::error::ApiError;
+use crate::models::SkinManifest;
+use crate::utils::resolve_within;
+
+/// 与皮肤 zip 同名的预览图扩展名
+const PREVIEW_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "webp"];
+
+/// 在线皮肤文件信息（已标记来源）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GithubSkin {
+    pub name: String,
+    /// 下载地址；本地文件夹来源时为 zip 文件的绝对路径
+    pub download_url: String,
+    pub size: u64,
+    /// GitHub blob SHA，用于检测皮肤更新（其他来源可能没有）
+    #[serde(default)]
+    pub sha: Option<String>,
+    /// 来源名称
+    #[serde(default)]
+    pub source: String,
+    /// 作者、版本、简介和预览图 URL（来自同名 .json 清单和预览图）
+    #[serde(flatten)]
+    pub manifest: SkinManifest,
+}
+
+/// 皮肤来源类型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SkinSourceKind {
+    /// GitHub 仓库中的某个目录
+    Github {
+        owner: String,
+        repo: String,
+        #[serde(default)]
+        branch: Option<String>,
+        #[serde(default)]
+        path: Option<String>,
+    },
+    /// 返回皮肤列表 JSON 的 HTTP 地址
+    HttpIndex { url: String },
+    /// 本地文件夹中的 zip 文件
+    Local { path: String },
+}
+
+/// 皮肤来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkinSource {
+    pub name: String,
+    /// 优先级，数值越大越靠前
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(flatten)]
+    pub kind: SkinSourceKind,
+}
+
+impl SkinSource {
+    /// 内置的官方皮肤仓库
+    pub fn official() -> Self {
+        SkinSource {
+            name: "MajdataPlay-Skins".to_string(),
+            priority: 0,
+            kind: SkinSourceKind::Github {
+                owner: "teamMajdata".to_string(),
+                repo: "MajdataPlay-Skins".to_string(),
+                branch: None,
+                path: None,
+            },
+        }
+    }
+
+    /// 获取该来源的皮肤列表
+    pub async fn fetch(&self, client: &reqwest::Client) -> Result<Vec<GithubSkin>, ApiError> {
+        let mut skins = match &self.kind {
+            SkinSourceKind::Github { owner, repo, branch, path } => {
+                fetch_github(client, owner, repo, branch.as_deref(), path.as_deref()).await?
+            }
+            SkinSourceKind::HttpIndex { url } => fetch_http_index(client, url).await?,
+            SkinSourceKind::Local { path } => list_local(path)?,
+        };
+        for skin in &mut skins {
+            skin.source = self.name.clone();
+        }
+        Ok(skins)
+    }
+
+    /// 解析本地文件夹来源中的皮肤 zip 路径
+    ///
+    /// 只有该来源为本地文件夹、且 `path` 规范化后位于该文件夹内的 zip 文件时返回规范化后的路径。
+    pub fn resolve_local_zip(&self, path: &str) -> Option<PathBuf> {
+        let SkinSourceKind::Local { path: dir } = &self.kind else {
+            return None;
+        };
+        let dir = std::fs::canonicalize(dir).ok()?;
+        let file = std::fs::canonicalize(path).ok()?;
+        let is_zip = file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+        (is_zip && file.is_file() && file.starts_with(&dir)).then_some(file)
+    }
+}
+
+/// GitHub contents API 条目
+#[derive(Deserialize)]
+struct GithubFile {
+    name: String,
+    download_url: Option<String>,
+    size: u64,
+    #[serde(default)]
+    sha: Option<String>,
+}
+
+/// 从 GitHub 仓库目录获取文件列表
+async fn fetch_github(
+    client: &reqwest::Client,
+    owner: &str,
+    repo: &str,
+    branch: Option<&str>,
+    path: Option<&str>,
+) -> Result<Vec<GithubSkin>, ApiError> {
+    let path = path.unwrap_or("").trim_matches('/');
+    let mut url = format!(
+        "https://api.github.com/repos/{}/{}/contents/{}",
+        urlencoding::encode(owner),
+        urlencoding::encode(repo),
+        path
+    );
+    if let Some(branch) = branch.filter(|b| !b.is_empty()) {
+        url = format!("{}?ref={}", url, urlencoding::encode(branch));
+    }
+
+    // 经过 API 缓存：条件请求命中 304 时不计入 GitHub 未认证请求限额
+    let request = client.get(&url);
+    let files: Vec<GithubFile> = cache::get_json_cached(request, &url, CachePolicy::GITHUB_CONTENTS).await?;
+
+    // 只保留有 download_url 的文件
+    let files: Vec<GithubSkin> = files
+        .into_iter()
+        .filter_map(|f| {
+            f.download_url.map(|url| GithubSkin {
+                name: f.name,
+                download_url: url,
+                size: f.size,
+                sha: f.sha,
+                source: String::new(),
+                manifest: SkinManifest::default(),
+            })
+        })
+        .collect();
+
+    let (mut skins, manifests) = split_sidecars(files);
+    for (skin, manifest_url) in skins.iter_mut().zip(manifests) {
+        if let Some(url) = manifest_url {
+            match cache::get_json_cached::<SkinManifest>(client.get(&url), &url, CachePolicy::SKIN_INDEX).await {
+                Ok(manifest) => merge_manifest(skin, manifest),
+                Err(e) => tracing::warn!("获取皮肤清单失败 {}: {}", url, e),
+            }
+        }
+    }
+    Ok(skins)
+}
+
+/// 将与 zip 同名的 .json 清单和预览图从文件列表中分离出来
+///
+/// 返回去掉附属文件后的列表（预览图已填入），以及每个条目对应的清单地址。
+fn split_sidecars(files: Vec<GithubSkin>) -> (Vec<GithubSkin>, Vec<Option<String>>) {
+    let zip_stems: Vec<String> = files
+        .iter()
+        .filter_map(|f| zip_stem(&f.name))
+        .map(|stem| stem.to_lowercase())
+        .collect();
+
+    // 附属文件：文件名（不含扩展名）与某个 zip 相同的 .json 或图片
+    let is_sidecar = |name: &str| -> bool {
+        let Some((stem, ext)) = name.rsplit_once('.') else {
+            return false;
+        };
+        let ext = ext.to_lowercase();
+        (ext == "json" || PREVIEW_EXTENSIONS.contains(&ext.as_str()))
+            && zip_stems.contains(&stem.to_lowercase())
+    };
+    let find = |files: &[GithubSkin], file_name: &str| -> Option<String> {
+        files
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(file_name))
+            .map(|f| f.download_url.clone())
+    };
+
+    let mut skins = Vec::new();
+    let mut manifests = Vec::new();
+    for file in files.iter().filter(|f| !is_sidecar(&f.name)) {
+        let mut skin = file.clone();
+        let mut manifest_url = None;
+        if let Some(stem) = zip_stem(&file.name) {
+            manifest_url = find(&files, &format!("{}.json", stem));
+            skin.manifest.preview = PREVIEW_EXTENSIONS
+                .iter()
+                .find_map(|ext| find(&files, &format!("{}.{}", stem, ext)));
+        }
+        skins.push(skin);
+        manifests.push(manifest_url);
+    }
+    (skins, manifests)
+}
+
+/// zip 文件名去掉扩展名，非 zip 文件返回 `None`
+fn zip_stem(name: &str) -> Option<&str> {
+    let (stem, ext) = name.rsplit_once('.')?;
+    ext.eq_ignore_ascii_case("zip").then_some(stem)
+}
+
+/// 合并清单内容；清单中的预览图为绝对 URL 时覆盖同名图片
+fn merge_manifest(skin: &mut GithubSkin, manifest: SkinManifest) {
+    let preview = manifest.preview
+        .filter(|p| p.starts_with("http://") || p.starts_with("https://"))
+        .or(skin.manifest.preview.take());
+    skin.manifest = SkinManifest { preview, ..manifest };
+}
+
+/// HTTP 索引条目，兼容 `url` 字段名
+#[derive(Deserialize)]
+struct IndexEntry {
+    name: String,
+    #[serde(alias = "url")]
+    download_url: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    sha: Option<String>,
+    #[serde(flatten)]
+    manifest: SkinManifest,
+}
+
+/// 从 HTTP JSON 索引获取皮肤列表
+async fn fetch_http_index(client: &reqwest::Client, url: &str) -> Result<Vec<GithubSkin>, ApiError> {
+    let entries: Vec<IndexEntry> = cache::get_json_cached(client.get(url), url, CachePolicy::SKIN_INDEX).await?;
+    Ok(entries
+        .into_iter()
+        .map(|e| GithubSkin {
+            name: e.name,
+            download_url: e.download_url,
+            size: e.size,
+            sha: e.sha,
+            source: String::new(),
+            manifest: e.manifest,
+        })
+        .collect())
+}
+
+/// 列出本地文件夹中的 zip 文件
+fn list_local(dir: &str) -> Result<Vec<GithubSkin>, ApiError> {
+    let entries = std::fs::read_dir(Path::new(dir))
+        .map_err(|e| ApiError::Config(format!("读取本地皮肤目录失败 {}: {}", dir, e)))?;
+
+    Ok(entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let is_zip = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+            if !is_zip || !path.is_file() {
+                return None;
+            }
+            Some(GithubSkin {
+                name: entry.file_name().to_string_lossy().to_string(),
+                download_url: path.to_string_lossy().to_string(),
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                sha: None,
+                source: String::new(),
+                manifest: read_local_sidecar(&path),
+            })
+        })
+        .collect())
+}
+
+/// 读取本地 zip 旁边的同名清单和预览图
+fn read_local_sidecar(zip_path: &Path) -> SkinManifest {
+    let mut manifest: SkinManifest = std::fs::read_to_string(zip_path.with_extension("json"))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    let preview = manifest.preview
+        .as_deref()
+        .and_then(|p| zip_path.parent().and_then(|dir| resolve_within(dir, p)))
+        .filter(|p| p.is_file())
+        .or_else(|| {
+            PREVIEW_EXTENSIONS
+                .iter()
+                .map(|ext| zip_path.with_extension(ext))
+                .find(|p| p.is_file())
+        });
+    manifest.preview = preview.map(|p| p.to_string_lossy().to_string());
+    manifest
+}
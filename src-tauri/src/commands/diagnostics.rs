@@ -0,0 +1,400 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::State;
+use crate::commands::launch::GAME_OUTPUT_DIR_NAME;
+use crate::launcher::LaunchRunner;
+use crate::logging::{self, reader::{LogEntry, LogQuery}};
+use crate::settings::SettingsStore;
+use crate::utils::unity_log::{extract_exceptions, LogException};
+
+/// 每个日志文件最多读取的字节数（只保留末尾部分）
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+
+/// 打包的本工具游戏输出记录数量（最近的若干次）
+const MAX_GAME_OUTPUT_LOGS: usize = 3;
+
+/// 打包的本工具日志数量（最近的若干个）
+const MAX_HUB_LOGS: usize = 2;
+
+/// 默认返回的日志条数
+const DEFAULT_LOG_LIMIT: usize = 500;
+
+/// 诊断报告目录名（位于应用日志目录）
+const REPORT_DIR_NAME: &str = "reports";
+
+/// 找到的日志文件
+#[derive(Debug, Clone, Serialize)]
+pub struct GameLogFile {
+    pub path: String,
+    /// 在 zip 中的路径
+    pub archive_name: String,
+    pub size: u64,
+}
+
+/// 日志收集结果
+#[derive(Debug, Clone, Serialize)]
+pub struct GameLogReport {
+    pub zip_path: String,
+    pub files: Vec<GameLogFile>,
+    pub exceptions: Vec<LogException>,
+}
+
+/// 列出目录中符合条件的文件
+fn list_files(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(&filter))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 列出子目录
+fn list_dirs(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default()
+}
+
+fn is_log_file(name: &str) -> bool {
+    name.to_lowercase().ends_with(".log")
+}
+
+/// Unity 在用户目录下的日志位置：`<LocalLow>/<公司名>/<MajdataPlay*>/Player*.log`，
+/// 以及临时目录中的崩溃报告 `<Temp>/<公司名>/<MajdataPlay*>/Crashes/*/error.log`
+fn unity_user_logs(local_low: &Path, temp: Option<&Path>) -> Vec<PathBuf> {
+    let is_majdata = |dir: &Path| {
+        dir.file_name()
+            .is_some_and(|n| n.to_string_lossy().to_lowercase().contains("majdata"))
+    };
+
+    let mut files = Vec::new();
+    for company in list_dirs(local_low) {
+        for product in list_dirs(&company).into_iter().filter(|d| is_majdata(d)) {
+            files.extend(list_files(&product, |n| n.starts_with("Player") && is_log_file(n)));
+        }
+    }
+    if let Some(temp) = temp {
+        for company in list_dirs(temp) {
+            for product in list_dirs(&company).into_iter().filter(|d| is_majdata(d)) {
+                for crash in list_dirs(&product.join("Crashes")) {
+                    files.extend(list_files(&crash, |n| n.eq_ignore_ascii_case("error.log")));
+                }
+            }
+        }
+    }
+    files
+}
+
+/// 查找游戏日志：游戏目录、Windows 用户目录、Wine 前缀中的用户目录和原生 Linux 的 unity3d 配置目录
+fn find_game_logs(game_dir: &Path, runner: &LaunchRunner) -> Vec<(String, PathBuf)> {
+    let mut logs: Vec<(String, PathBuf)> = Vec::new();
+
+    for path in list_files(game_dir, is_log_file) {
+        logs.push(("game".to_string(), path));
+    }
+    for sub in ["Logs", "logs"] {
+        for path in list_files(&game_dir.join(sub), is_log_file) {
+            logs.push(("game/Logs".to_string(), path));
+        }
+    }
+
+    if let Some(profile) = std::env::var_os("USERPROFILE") {
+        let app_data = Path::new(&profile).join("AppData");
+        let temp = std::env::var_os("TEMP").map(PathBuf::from);
+        for path in unity_user_logs(&app_data.join("LocalLow"), temp.as_deref()) {
+            logs.push(("unity".to_string(), path));
+        }
+    }
+
+    if let Some(prefix) = runner.wine_prefix() {
+        for user in list_dirs(&prefix.join("drive_c").join("users")) {
+            let app_data = user.join("AppData");
+            let temp = app_data.join("Local").join("Temp");
+            for path in unity_user_logs(&app_data.join("LocalLow"), Some(&temp)) {
+                logs.push(("wine".to_string(), path));
+            }
+        }
+    }
+
+    if let Some(home) = std::env::var_os("HOME") {
+        for path in unity_user_logs(&Path::new(&home).join(".config").join("unity3d"), None) {
+            logs.push(("unity3d".to_string(), path));
+        }
+    }
+
+    // 同一文件可能通过不同路径找到（例如游戏目录就在用户目录下）
+    logs.sort_by(|a, b| a.1.cmp(&b.1));
+    logs.dedup_by(|a, b| a.1 == b.1);
+    logs
+}
+
+/// 读取日志末尾（最多 MAX_LOG_BYTES），返回内容和是否被截断
+fn read_log_tail(path: &Path) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let truncated = len > MAX_LOG_BYTES;
+    if truncated {
+        file.seek(SeekFrom::Start(len - MAX_LOG_BYTES))?;
+    }
+    let mut bytes = Vec::new();
+    file.take(MAX_LOG_BYTES).read_to_end(&mut bytes)?;
+    Ok((bytes, truncated))
+}
+
+/// 系统信息
+fn system_info(game_dir: &Path, runner: &LaunchRunner) -> String {
+    let mut info = String::new();
+    let mut line = |key: &str, value: String| info.push_str(&format!("{}: {}\n", key, value));
+
+    line("Majdata Hub", env!("CARGO_PKG_VERSION").to_string());
+    line("Time", chrono::Local::now().to_rfc3339());
+    line("OS", format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH));
+    if let Some(version) = os_version() {
+        line("OS Version", version);
+    }
+    line(
+        "CPU Threads",
+        std::thread::available_parallelism().map(|n| n.to_string()).unwrap_or_default(),
+    );
+    line("Game Dir", game_dir.display().to_string());
+    let exe = game_dir.join("MajdataPlay.exe");
+    match std::fs::metadata(&exe) {
+        Ok(meta) => {
+            let modified = meta
+                .modified()
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+                .unwrap_or_default();
+            line("MajdataPlay.exe", format!("{} bytes, modified {}", meta.len(), modified));
+        }
+        Err(e) => line("MajdataPlay.exe", format!("missing ({})", e)),
+    }
+    line("Runner", format!("{:?}", runner.kind));
+    info
+}
+
+/// 操作系统版本
+fn os_version() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let text = std::fs::read_to_string("/etc/os-release").ok()?;
+        text.lines()
+            .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+            .map(|v| v.trim_matches('"').to_string())
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let output = std::process::Command::new("cmd").args(["/C", "ver"]).output().ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// 将异常列表格式化为文本
+fn format_exceptions(exceptions: &[LogException]) -> String {
+    let mut text = String::new();
+    for e in exceptions {
+        text.push_str(&format!("[{}:{}] (x{}) {}\n", e.source, e.line, e.count, e.message));
+        for frame in &e.stack {
+            text.push_str(&format!("    {}\n", frame));
+        }
+        text.push('\n');
+    }
+    if text.is_empty() {
+        text.push_str("未发现异常\n");
+    }
+    text
+}
+
+/// 收集日志并写入 zip（阻塞操作）
+fn build_report(
+    game_dir: &Path,
+    runner: &LaunchRunner,
+    game_output_dir: Option<PathBuf>,
+    zip_path: &Path,
+) -> Result<GameLogReport, String> {
+    let mut sources = find_game_logs(game_dir, runner);
+
+    // 本工具记录的最近几次游戏输出
+    if let Some(dir) = game_output_dir {
+        let mut outputs = list_files(&dir, is_log_file);
+        outputs.sort();
+        for path in outputs.into_iter().rev().take(MAX_GAME_OUTPUT_LOGS) {
+            sources.push(("hub-game-output".to_string(), path));
+        }
+    }
+    for path in logging::log_files().into_iter().take(MAX_HUB_LOGS) {
+        sources.push(("hub".to_string(), path));
+    }
+
+    if let Some(parent) = zip_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create report directory: {}", e))?;
+    }
+    let file = std::fs::File::create(zip_path)
+        .map_err(|e| format!("Failed to create report file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut files = Vec::new();
+    let mut exceptions = Vec::new();
+    for (index, (category, path)) in sources.iter().enumerate() {
+        let (bytes, truncated) = match read_log_tail(path) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("读取日志失败 {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        // 加序号避免不同目录下的同名文件（如多个 Player.log）冲突
+        let archive_name = format!("logs/{}/{:02}_{}", category, index, file_name);
+
+        zip.start_file(archive_name.as_str(), options)
+            .and_then(|_| zip.write_all(&bytes).map_err(Into::into))
+            .map_err(|e| format!("Failed to write report: {}", e))?;
+
+        let text = String::from_utf8_lossy(&bytes);
+        exceptions.extend(extract_exceptions(&archive_name, &text));
+        if truncated {
+            tracing::info!("日志过大，仅保留末尾 {} 字节: {:?}", MAX_LOG_BYTES, path);
+        }
+        files.push(GameLogFile {
+            path: path.to_string_lossy().to_string(),
+            archive_name,
+            size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        });
+    }
+
+    let exceptions_json = serde_json::to_string_pretty(&exceptions)
+        .map_err(|e| format!("Failed to serialize exceptions: {}", e))?;
+    let entries = [
+        ("exceptions.txt", format_exceptions(&exceptions)),
+        ("exceptions.json", exceptions_json),
+        ("system.txt", system_info(game_dir, runner)),
+    ];
+    for (name, content) in entries {
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(content.as_bytes()).map_err(Into::into))
+            .map_err(|e| format!("Failed to write report: {}", e))?;
+    }
+    zip.finish().map_err(|e| format!("Failed to finish report: {}", e))?;
+
+    Ok(GameLogReport {
+        zip_path: zip_path.to_string_lossy().to_string(),
+        files,
+        exceptions,
+    })
+}
+
+/// Tauri命令：收集游戏日志并打包
+///
+/// 查找设置中的游戏目录和 Unity 默认位置（含运行器的 Wine 前缀）中的日志，提取异常和堆栈，
+/// 与 MajdataHub.log、系统信息一起打包为 zip。`output_path` 为空时写入应用日志目录下的 reports 文件夹。
+#[tauri::command]
+pub async fn collect_game_logs(
+    settings: State<'_, SettingsStore>,
+    output_path: Option<String>
+) -> Result<GameLogReport, String> {
+    let settings = settings.get();
+    let game_dir = settings.game_dir();
+    tracing::info!("收集游戏日志: {:?}", game_dir);
+    let start_time = std::time::Instant::now();
+
+    let log_dir = logging::app_log_dir();
+    let zip_path = match output_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => log_dir
+            .as_ref()
+            .map(|dir| dir.join(REPORT_DIR_NAME))
+            .ok_or_else(|| "无法获取应用日志目录，请指定输出路径".to_string())?
+            .join(format!("MajdataHub_report_{}.zip", chrono::Local::now().format("%Y%m%d_%H%M%S"))),
+    };
+    let game_output_dir = log_dir.map(|dir| dir.join(GAME_OUTPUT_DIR_NAME));
+    let runner = settings.runner();
+
+    let report = tokio::task::spawn_blocking(move || {
+        build_report(&game_dir, &runner, game_output_dir, &zip_path)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| {
+        tracing::error!("收集游戏日志失败: {}", e);
+        e
+    })?;
+
+    let elapsed = start_time.elapsed();
+    tracing::info!(
+        "游戏日志已打包: {} ({} 个日志, {} 个异常, {:.2}s)",
+        report.zip_path,
+        report.files.len(),
+        report.exceptions.len(),
+        elapsed.as_secs_f64()
+    );
+    Ok(report)
+}
+
+/// 解析 RFC 3339 时间
+fn parse_time(value: Option<String>) -> Result<Option<chrono::DateTime<chrono::FixedOffset>>, String> {
+    value
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| chrono::DateTime::parse_from_rfc3339(v.trim()).map_err(|e| format!("无效的时间 {}: {}", v, e)))
+        .transpose()
+}
+
+/// Tauri命令：获取最近的应用日志
+///
+/// `level` 为最低级别（如 `warn` 只返回警告和错误），`since`/`until` 为 RFC 3339 时间，
+/// `limit` 默认 500 条。结果按时间先后排列。
+#[tauri::command]
+pub async fn get_recent_logs(
+    level: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>
+) -> Result<Vec<LogEntry>, String> {
+    let level = level
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.trim().parse::<tracing::Level>().map_err(|_| format!("无效的日志级别: {}", l)))
+        .transpose()?;
+    let query = LogQuery {
+        level,
+        since: parse_time(since)?,
+        until: parse_time(until)?,
+        limit: limit.unwrap_or(DEFAULT_LOG_LIMIT),
+    };
+
+    tokio::task::spawn_blocking(move || logging::reader::read_recent(&logging::log_files(), &query))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Tauri命令：运行时修改日志级别
+///
+/// `level` 可以是单个级别（`debug`）或 `RUST_LOG` 格式的规则，返回生效后的规则。
+#[tauri::command]
+pub fn set_log_level(level: String) -> Result<String, String> {
+    let applied = logging::set_filter(&level)?;
+    tracing::info!("日志级别已修改为: {}", applied);
+    Ok(applied)
+}
+
+/// Tauri命令：获取当前日志级别规则
+#[tauri::command]
+pub fn get_log_level() -> Option<String> {
+    logging::current_filter()
+}
+
+/// Tauri命令：开启或关闭后端日志实时转发（`backend-log` 事件）
+#[tauri::command]
+pub fn set_log_stream(enabled: bool) {
+    logging::stream::set_enabled(enabled);
+}
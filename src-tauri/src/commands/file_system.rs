@@ -1,16 +1,16 @@
 use std::env;
 use std::path::Path;
 use std::fs;
-use std::process::Command;
 use serde::{Deserialize, Serialize};
-
-/// 游戏启动选项
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LaunchOption {
-    pub id: String,
-    pub label: String,
-    pub description: String,
-}
+use walkdir::WalkDir;
+use tauri::State;
+use crate::launcher::guard::{self, GuardedError};
+use crate::commands::skin::skin_dir;
+use crate::launcher::GameProcesses;
+use crate::models::{SkinManifest, SKIN_MANIFEST_FILE};
+use crate::settings::SettingsStore;
+use crate::utils::media::{IMAGE_EXTENSIONS, AUDIO_EXTENSIONS, VIDEO_EXTENSIONS};
+use crate::utils::resolve_within;
 
 /// 谱面信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,8 +27,16 @@ pub struct ChartInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkinInfo {
     pub name: String,
+    #[serde(flatten)]
+    pub manifest: SkinManifest,
+    /// 占用磁盘大小（字节）
+    pub size: u64,
+    pub file_count: u64,
 }
 
+/// 未在清单中指定预览图时依次查找的文件名
+const SKIN_PREVIEW_CANDIDATES: [&str; 4] = ["preview.png", "preview.jpg", "preview.jpeg", "preview.webp"];
+
 /// Tauri命令：获取应用程序可执行文件的完整路径
 #[tauri::command]
 pub fn get_app_exe_path() -> Result<String, String> {
@@ -66,106 +74,6 @@ pub fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-/// Tauri命令：获取游戏启动选项列表（内置）
-#[tauri::command]
-pub fn get_launch_options() -> Vec<LaunchOption> {
-    vec![
-        LaunchOption {
-            id: "default".to_string(),
-            label: "默认启动".to_string(),
-            description: "使用默认设置启动游戏".to_string(),
-        },
-        LaunchOption {
-            id: "d3d11".to_string(),
-            label: "强制使用 D3D11".to_string(),
-            description: "强制使用 Direct3D 11 渲染".to_string(),
-        },
-        LaunchOption {
-            id: "d3d12".to_string(),
-            label: "强制使用 D3D12".to_string(),
-            description: "强制使用 Direct3D 12 渲染".to_string(),
-        },
-        LaunchOption {
-            id: "opengl".to_string(),
-            label: "强制使用 OpenGL".to_string(),
-            description: "强制使用 OpenGL Core 渲染".to_string(),
-        },
-        LaunchOption {
-            id: "vulkan".to_string(),
-            label: "强制使用 Vulkan".to_string(),
-            description: "强制使用 Vulkan 渲染".to_string(),
-        },
-        LaunchOption {
-            id: "test".to_string(),
-            label: "测试模式".to_string(),
-            description: "进入游戏测试模式".to_string(),
-        },
-        LaunchOption {
-            id: "edit".to_string(),
-            label: "MajdataEdit".to_string(),
-            description: "打开谱面编辑器".to_string(),
-        },
-    ]
-}
-
-/// Tauri命令：根据启动选项ID启动游戏
-#[tauri::command]
-pub fn launch_game(game_dir: String, option_id: String) -> Result<(), String> {
-    tracing::info!("准备启动游戏，游戏目录: {}，启动选项: {}", game_dir, option_id);
-    
-    let game_exe = Path::new(&game_dir).join("MajdataPlay.exe");
-    
-    if !game_exe.exists() {
-        tracing::error!("游戏程序不存在: {:?}", game_exe);
-        return Err(format!("游戏程序不存在: {}", game_exe.display()));
-    }
-    
-    #[cfg(target_os = "windows")]
-    {
-        let args: Vec<&str> = match option_id.as_str() {
-            "default" => vec![],
-            "d3d11" => vec!["-force-d3d11"],
-            "d3d12" => vec!["-force-d3d12"],
-            "opengl" => vec!["-force-glcore"],
-            "vulkan" => vec!["-force-vulkan"],
-            "test" => vec!["--test-mode"],
-            "edit" => vec!["--view-mode"],
-            _ => {
-                tracing::error!("未知的启动选项: {}", option_id);
-                return Err(format!("未知的启动选项: {}", option_id));
-            }
-        };
-        
-        tracing::info!("使用参数启动游戏: {:?}", args);
-        
-        // 启动游戏
-        match Command::new(&game_exe)
-            .args(&args)
-            .current_dir(&game_dir)
-            .spawn()
-        {
-            Ok(_) => {
-                tracing::info!("游戏启动成功");
-                // 如果是编辑模式，等待一下然后启动编辑器
-                if option_id == "edit" {
-                    // 注意：这里只启动游戏，编辑器需要用户手动启动
-                    // 或者可以提示用户等待游戏打开后再启动编辑器
-                }
-                Ok(())
-            },
-            Err(e) => {
-                tracing::error!("启动游戏失败: {}", e);
-                Err(format!("启动游戏失败: {}", e))
-            }
-        }
-    }
-    
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("此功能仅在 Windows 上可用".to_string())
-    }
-}
-
 /// Tauri命令：列出指定目录下的所有 .bat 文件（已弃用，保留用于兼容）
 #[tauri::command]
 pub fn list_bat_files(dir_path: String) -> Result<Vec<String>, String> {
@@ -201,8 +109,10 @@ pub fn list_bat_files(dir_path: String) -> Result<Vec<String>, String> {
 }
 
 /// Tauri命令：执行指定的 .bat 文件（已弃用，保留用于兼容）
+///
+/// 使用设置中的游戏运行器。
 #[tauri::command]
-pub fn execute_bat_file(dir_path: String, bat_file: String) -> Result<(), String> {
+pub fn execute_bat_file(settings: State<'_, SettingsStore>, dir_path: String, bat_file: String) -> Result<(), String> {
     tracing::info!("执行 BAT 文件: {}/{}", dir_path, bat_file);
     
     let bat_path = Path::new(&dir_path).join(&bat_file);
@@ -212,41 +122,31 @@ pub fn execute_bat_file(dir_path: String, bat_file: String) -> Result<(), String
         return Err(format!("BAT 文件不存在: {}", bat_path.display()));
     }
     
-    #[cfg(target_os = "windows")]
-    {
-        match Command::new("cmd")
-            .args(&["/C", "start", "", bat_path.to_str().unwrap()])
-            .current_dir(&dir_path)
-            .spawn()
-        {
-            Ok(_) => {
-                tracing::info!("BAT 文件执行成功");
-                Ok(())
-            },
-            Err(e) => {
-                tracing::error!("执行 BAT 文件失败: {}", e);
-                Err(format!("执行 BAT 文件失败: {}", e))
-            }
+    let runner = settings.get().runner();
+    match runner.bat_command(&bat_path, Path::new(&dir_path))?.spawn() {
+        Ok(_) => {
+            tracing::info!("BAT 文件执行成功");
+            Ok(())
+        },
+        Err(e) => {
+            tracing::error!("执行 BAT 文件失败: {}", e);
+            Err(format!("执行 BAT 文件失败: {}", e))
         }
     }
-    
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("此功能仅在 Windows 上可用".to_string())
-    }
 }
 
-/// Tauri命令：列出所有谱面分类
+/// Tauri命令：列出所有谱面分类（设置中游戏目录下的 MaiCharts）
 #[tauri::command]
-pub fn list_chart_categories(maicharts_dir: String) -> Result<Vec<String>, String> {
-    let path = Path::new(&maicharts_dir);
+pub fn list_chart_categories(settings: State<'_, SettingsStore>) -> Result<Vec<String>, String> {
+    let maicharts_dir = settings.get().maicharts_dir();
+    let path = maicharts_dir.as_path();
     
     if !path.exists() {
         return Ok(Vec::new());
     }
     
     if !path.is_dir() {
-        return Err(format!("路径不是目录: {}", maicharts_dir));
+        return Err(format!("路径不是目录: {}", maicharts_dir.display()));
     }
     
     let mut categories = Vec::new();
@@ -270,10 +170,17 @@ pub fn list_chart_categories(maicharts_dir: String) -> Result<Vec<String>, Strin
     Ok(categories)
 }
 
+/// 检查谱面文件夹中是否存在任一 `{stem}.{ext}` 组合的文件
+fn has_any_file(chart_path: &Path, stems: &[&str], extensions: &[&str]) -> bool {
+    stems.iter().any(|stem| {
+        extensions.iter().any(|ext| chart_path.join(format!("{}.{}", stem, ext)).exists())
+    })
+}
+
 /// Tauri命令：列出某个分类下的所有谱面
 #[tauri::command]
-pub fn list_charts_in_category(maicharts_dir: String, category: String) -> Result<Vec<ChartInfo>, String> {
-    let category_path = Path::new(&maicharts_dir).join(&category);
+pub fn list_charts_in_category(settings: State<'_, SettingsStore>, category: String) -> Result<Vec<ChartInfo>, String> {
+    let category_path = settings.get().maicharts_dir().join(&category);
     
     if !category_path.exists() {
         return Ok(Vec::new());
@@ -296,10 +203,10 @@ pub fn list_charts_in_category(maicharts_dir: String, category: String) -> Resul
                             charts.push(ChartInfo {
                                 name: name.to_string(),
                                 category: category.clone(),
-                                has_bg: chart_path.join("bg.jpg").exists() || chart_path.join("bg.png").exists(),
-                                has_track: chart_path.join("track.mp3").exists() || chart_path.join("track.ogg").exists(),
+                                has_bg: has_any_file(&chart_path, &["bg"], &IMAGE_EXTENSIONS),
+                                has_track: has_any_file(&chart_path, &["track"], &AUDIO_EXTENSIONS),
                                 has_maidata: chart_path.join("maidata.txt").exists(),
-                                has_video: chart_path.join("pv.mp4").exists() || chart_path.join("bg.mp4").exists(),
+                                has_video: has_any_file(&chart_path, &["pv", "bg"], &VIDEO_EXTENSIONS),
                             });
                         }
                     }
@@ -315,15 +222,21 @@ pub fn list_charts_in_category(maicharts_dir: String, category: String) -> Resul
 
 /// Tauri命令：删除谱面
 #[tauri::command]
-pub fn delete_chart(maicharts_dir: String, category: String, chart_name: String) -> Result<(), String> {
-    let chart_path = Path::new(&maicharts_dir).join(&category).join(&chart_name);
+pub fn delete_chart(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    category: String,
+    chart_name: String
+) -> Result<(), GuardedError> {
+    let chart_path = settings.get().maicharts_dir().join(&category).join(&chart_name);
+    guard::ensure_not_running(&games, &chart_path)?;
     
     if !chart_path.exists() {
-        return Err(format!("谱面不存在: {}", chart_path.display()));
+        return Err(format!("谱面不存在: {}", chart_path.display()).into());
     }
     
     if !chart_path.is_dir() {
-        return Err(format!("路径不是目录: {}", chart_path.display()));
+        return Err(format!("路径不是目录: {}", chart_path.display()).into());
     }
     
     match fs::remove_dir_all(&chart_path) {
@@ -333,20 +246,29 @@ pub fn delete_chart(maicharts_dir: String, category: String, chart_name: String)
         },
         Err(e) => {
             tracing::error!("删除谱面失败: {}", e);
-            Err(format!("删除谱面失败: {}", e))
+            Err(format!("删除谱面失败: {}", e).into())
         }
     }
 }
 
 /// Tauri命令：移动谱面到另一个分类
 #[tauri::command]
-pub fn move_chart(maicharts_dir: String, from_category: String, to_category: String, chart_name: String) -> Result<(), String> {
-    let from_path = Path::new(&maicharts_dir).join(&from_category).join(&chart_name);
-    let to_category_path = Path::new(&maicharts_dir).join(&to_category);
+pub fn move_chart(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    from_category: String,
+    to_category: String,
+    chart_name: String
+) -> Result<(), GuardedError> {
+    let maicharts_dir = settings.get().maicharts_dir();
+    let from_path = maicharts_dir.join(&from_category).join(&chart_name);
+    let to_category_path = maicharts_dir.join(&to_category);
     let to_path = to_category_path.join(&chart_name);
+    guard::ensure_not_running(&games, &from_path)?;
+    guard::ensure_not_running(&games, &to_category_path)?;
     
     if !from_path.exists() {
-        return Err(format!("源谱面不存在: {}", from_path.display()));
+        return Err(format!("源谱面不存在: {}", from_path.display()).into());
     }
     
     // 确保目标分类存在
@@ -357,7 +279,7 @@ pub fn move_chart(maicharts_dir: String, from_category: String, to_category: Str
     
     // 检查目标位置是否已存在同名谱面
     if to_path.exists() {
-        return Err(format!("目标位置已存在同名谱面: {}", to_path.display()));
+        return Err(format!("目标位置已存在同名谱面: {}", to_path.display()).into());
     }
     
     match fs::rename(&from_path, &to_path) {
@@ -367,18 +289,23 @@ pub fn move_chart(maicharts_dir: String, from_category: String, to_category: Str
         },
         Err(e) => {
             tracing::error!("移动谱面失败: {}", e);
-            Err(format!("移动谱面失败: {}", e))
+            Err(format!("移动谱面失败: {}", e).into())
         }
     }
 }
 
 /// Tauri命令：创建新的谱面分类
 #[tauri::command]
-pub fn create_chart_category(maicharts_dir: String, category: String) -> Result<(), String> {
-    let category_path = Path::new(&maicharts_dir).join(&category);
+pub fn create_chart_category(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    category: String
+) -> Result<(), GuardedError> {
+    let category_path = settings.get().maicharts_dir().join(&category);
+    guard::ensure_not_running(&games, &category_path)?;
     
     if category_path.exists() {
-        return Err(format!("分类已存在: {}", category));
+        return Err(format!("分类已存在: {}", category).into());
     }
     
     match fs::create_dir_all(&category_path) {
@@ -388,7 +315,7 @@ pub fn create_chart_category(maicharts_dir: String, category: String) -> Result<
         },
         Err(e) => {
             tracing::error!("创建分类失败: {}", e);
-            Err(format!("创建分类失败: {}", e))
+            Err(format!("创建分类失败: {}", e).into())
         }
     }
 }
@@ -410,10 +337,59 @@ pub fn create_directory(path: String) -> Result<(), String> {
     }
 }
 
-/// Tauri命令：列出所有皮肤
+/// 读取皮肤文件夹中的清单，并将预览图解析为绝对路径
+fn read_skin_manifest(skin_path: &Path) -> SkinManifest {
+    let mut manifest: SkinManifest = fs::read_to_string(skin_path.join(SKIN_MANIFEST_FILE))
+        .ok()
+        .and_then(|text| match serde_json::from_str(&text) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                tracing::warn!("解析皮肤清单失败 {:?}: {}", skin_path, e);
+                None
+            }
+        })
+        .unwrap_or_default();
+
+    // 清单来自下载内容，预览图路径必须位于皮肤文件夹内
+    let preview = manifest.preview
+        .as_deref()
+        .and_then(|p| resolve_within(skin_path, p))
+        .filter(|p| p.is_file())
+        .or_else(|| {
+            SKIN_PREVIEW_CANDIDATES
+                .iter()
+                .map(|name| skin_path.join(name))
+                .find(|p| p.is_file())
+        });
+    manifest.preview = preview.map(|p| p.to_string_lossy().to_string());
+    manifest
+}
+
+/// 统计文件夹占用大小和文件数
+fn dir_size(path: &Path) -> (u64, u64) {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .fold((0, 0), |(size, count), e| {
+            (size + e.metadata().map(|m| m.len()).unwrap_or(0), count + 1)
+        })
+}
+
+/// Tauri命令：列出所有皮肤（设置中游戏目录下的 Skins）
+///
+/// 统计每个皮肤的大小需要遍历全部文件，在阻塞线程池中执行。
 #[tauri::command]
-pub fn list_skins(skins_dir: String) -> Result<Vec<SkinInfo>, String> {
-    let path = Path::new(&skins_dir);
+pub async fn list_skins(settings: State<'_, SettingsStore>) -> Result<Vec<SkinInfo>, String> {
+    let skins_dir = settings.get().skins_dir();
+    tokio::task::spawn_blocking(move || scan_skins(&skins_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 读取皮肤目录中的所有皮肤
+fn scan_skins(skins_dir: &Path) -> Result<Vec<SkinInfo>, String> {
+    let path = skins_dir;
     
     if !path.exists() {
         // 如果目录不存在，尝试创建
@@ -423,7 +399,7 @@ pub fn list_skins(skins_dir: String) -> Result<Vec<SkinInfo>, String> {
     }
     
     if !path.is_dir() {
-        return Err(format!("路径不是目录: {}", skins_dir));
+        return Err(format!("路径不是目录: {}", skins_dir.display()));
     }
     
     let mut skins = Vec::new();
@@ -433,11 +409,15 @@ pub fn list_skins(skins_dir: String) -> Result<Vec<SkinInfo>, String> {
             for entry in entries {
                 if let Ok(entry) = entry {
                     let entry_path = entry.path();
-                    // 列出文件夹（皮肤是解压后的文件夹）
+                    // 列出文件夹（皮肤是解压后的文件夹），跳过解压中的临时文件夹
                     if entry_path.is_dir() {
-                        if let Some(name) = entry.file_name().to_str() {
+                        if let Some(name) = entry.file_name().to_str().filter(|n| !n.starts_with('.')) {
+                            let (size, file_count) = dir_size(&entry_path);
                             skins.push(SkinInfo {
                                 name: name.to_string(),
+                                manifest: read_skin_manifest(&entry_path),
+                                size,
+                                file_count,
                             });
                         }
                     }
@@ -453,15 +433,20 @@ pub fn list_skins(skins_dir: String) -> Result<Vec<SkinInfo>, String> {
 
 /// Tauri命令：删除皮肤
 #[tauri::command]
-pub fn delete_skin(skins_dir: String, skin_name: String) -> Result<(), String> {
-    let skin_path = Path::new(&skins_dir).join(&skin_name);
+pub fn delete_skin(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    skin_name: String
+) -> Result<(), GuardedError> {
+    let skin_path = skin_dir(&settings.get().skins_dir(), &skin_name)?;
+    guard::ensure_not_running(&games, &skin_path)?;
     
     if !skin_path.exists() {
-        return Err(format!("皮肤不存在: {}", skin_path.display()));
+        return Err(format!("皮肤不存在: {}", skin_path.display()).into());
     }
     
     if !skin_path.is_dir() {
-        return Err(format!("路径不是目录: {}", skin_path.display()));
+        return Err(format!("路径不是目录: {}", skin_path.display()).into());
     }
     
     match fs::remove_dir_all(&skin_path) {
@@ -471,7 +456,7 @@ pub fn delete_skin(skins_dir: String, skin_name: String) -> Result<(), String> {
         },
         Err(e) => {
             tracing::error!("删除皮肤失败: {}", e);
-            Err(format!("删除皮肤失败: {}", e))
+            Err(format!("删除皮肤失败: {}", e).into())
         }
     }
 }
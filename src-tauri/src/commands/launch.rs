@@ -0,0 +1,241 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager, State};
+use crate::launcher::guard;
+use crate::launcher::process::{self, GameExit, RunningGame};
+use crate::launcher::profile::{self, hook_command};
+use crate::launcher::{GameProcesses, LaunchProfile};
+use crate::logging;
+use crate::settings::SettingsStore;
+
+/// 获取应用配置目录
+fn config_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))
+}
+
+/// Tauri命令：获取游戏启动配置列表
+///
+/// 配置保存在应用配置目录的 launch_profiles.json 中，首次调用时写入内置配置。
+#[tauri::command]
+pub fn get_launch_options(app: tauri::AppHandle) -> Result<Vec<LaunchProfile>, String> {
+    profile::load_profiles(&config_dir(&app)?)
+}
+
+/// Tauri命令：新增或修改启动配置（按 ID 匹配）
+#[tauri::command]
+pub fn save_launch_profile(app: tauri::AppHandle, profile: LaunchProfile) -> Result<Vec<LaunchProfile>, String> {
+    let id = profile.id.trim().to_string();
+    if id.is_empty() {
+        return Err("启动配置 ID 不能为空".to_string());
+    }
+    if profile.label.trim().is_empty() {
+        return Err("启动配置名称不能为空".to_string());
+    }
+    tracing::info!("保存启动配置: {} ({})", profile.label, id);
+
+    let profile = LaunchProfile { id: id.clone(), ..profile };
+    profile::update_profiles(&config_dir(&app)?, move |profiles| {
+        match profiles.iter_mut().find(|p| p.id == id) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+        Ok(())
+    })
+}
+
+/// Tauri命令：删除启动配置
+#[tauri::command]
+pub fn delete_launch_profile(app: tauri::AppHandle, id: String) -> Result<Vec<LaunchProfile>, String> {
+    tracing::info!("删除启动配置: {}", id);
+    profile::update_profiles(&config_dir(&app)?, |profiles| {
+        let before = profiles.len();
+        profiles.retain(|p| p.id != id);
+        if profiles.len() == before {
+            return Err(format!("启动配置不存在: {}", id));
+        }
+        Ok(())
+    })
+}
+
+/// Tauri命令：恢复内置启动配置（自定义配置会被清除）
+#[tauri::command]
+pub fn reset_launch_profiles(app: tauri::AppHandle) -> Result<Vec<LaunchProfile>, String> {
+    tracing::info!("恢复内置启动配置");
+    profile::update_profiles(&config_dir(&app)?, |profiles| {
+        *profiles = LaunchProfile::defaults();
+        Ok(())
+    })
+}
+
+/// 执行启动前钩子，等待其结束；非零退出码时取消启动
+fn run_pre_launch(hook: &str, profile: &LaunchProfile, working_dir: &Path) -> Result<(), String> {
+    tracing::info!("执行启动前命令: {}", hook);
+    let status = hook_command(hook, profile, working_dir)?
+        .status()
+        .map_err(|e| format!("执行启动前命令失败: {}", e))?;
+    if !status.success() {
+        tracing::error!("启动前命令失败: {}", status);
+        return Err(format!("启动前命令失败 ({}): {}", status, hook));
+    }
+    Ok(())
+}
+
+/// 游戏输出记录目录名（位于应用日志目录）
+pub const GAME_OUTPUT_DIR_NAME: &str = "game";
+
+/// 创建本次启动的输出记录文件
+fn create_output_log() -> Option<(PathBuf, File)> {
+    let dir = logging::app_log_dir()?.join(GAME_OUTPUT_DIR_NAME);
+    std::fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!("MajdataPlay_{}.log", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+    match File::create(&path) {
+        Ok(file) => Some((path, file)),
+        Err(e) => {
+            tracing::warn!("创建游戏输出记录失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 确认游戏目录中没有正在运行的游戏
+fn ensure_not_running(games: &GameProcesses, game_dir: &str) -> Result<(), String> {
+    guard::ensure_not_running(games, Path::new(game_dir)).map_err(|running| match running.pid {
+        Some(pid) => format!("游戏已在运行 (PID {})", pid),
+        None => "游戏已在运行".to_string(),
+    })
+}
+
+/// Tauri命令：获取由本工具启动、仍在运行的游戏进程
+#[tauri::command]
+pub fn get_running_games(games: State<'_, GameProcesses>) -> Vec<RunningGame> {
+    games.list()
+}
+
+/// Tauri命令：根据启动配置 ID 启动游戏
+///
+/// 游戏目录和运行器来自设置，未配置运行器时使用平台默认运行器（Windows 直接运行，其他平台使用 wine）。
+/// 进程记录在应用状态中，启动和退出时分别发送 `game-started`、`game-exited` 事件，
+/// 标准输出和标准错误写入应用日志目录下的 game 文件夹。
+/// 启动前命令可能运行较久，在阻塞线程池中等待，不会卡住界面。
+#[tauri::command]
+pub async fn launch_game(
+    app: tauri::AppHandle,
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    option_id: String
+) -> Result<RunningGame, String> {
+    let settings = settings.get();
+    let game_dir = settings.game_dir().to_string_lossy().to_string();
+    tracing::info!("准备启动游戏，游戏目录: {}，启动配置: {}", game_dir, option_id);
+
+    let game_exe = Path::new(&game_dir).join("MajdataPlay.exe");
+
+    if !game_exe.exists() {
+        tracing::error!("游戏程序不存在: {:?}", game_exe);
+        return Err(format!("游戏程序不存在: {}", game_exe.display()));
+    }
+
+    // 同一目录的游戏已在运行（包括不是由本工具启动的）
+    ensure_not_running(&games, &game_dir)?;
+
+    let profile = profile::load_profiles(&config_dir(&app)?)?
+        .into_iter()
+        .find(|p| p.id == option_id)
+        .ok_or_else(|| {
+            tracing::error!("未知的启动配置: {}", option_id);
+            format!("未知的启动配置: {}", option_id)
+        })?;
+    let working_dir = profile.resolve_working_dir(Path::new(&game_dir));
+
+    if let Some(hook) = profile.pre_launch.clone().filter(|h| !h.trim().is_empty()) {
+        let (hook_profile, hook_dir) = (profile.clone(), working_dir.clone());
+        tokio::task::spawn_blocking(move || run_pre_launch(&hook, &hook_profile, &hook_dir))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+        // 等待期间可能已从其他地方启动了游戏
+        ensure_not_running(&games, &game_dir)?;
+    }
+
+    let runner = settings.runner();
+    tracing::info!("使用参数启动游戏: {:?}，运行器: {:?}", profile.args, runner.kind);
+
+    // 先打开输出记录：能记录时通过管道读取输出，否则直接丢弃，避免无人读取的管道写满后游戏阻塞
+    let output_log = create_output_log();
+    let stdio = || if output_log.is_some() { Stdio::piped() } else { Stdio::null() };
+    let mut command = runner.command(&game_exe, &profile.args, &working_dir)?;
+    command.envs(&profile.env).stdout(stdio()).stderr(stdio());
+
+    // 启动游戏
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            tracing::error!("启动游戏失败: {}", e);
+            if let Some((path, _)) = &output_log {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(format!("启动游戏失败: {}", e));
+        }
+    };
+    tracing::info!("游戏启动成功，PID: {}", child.id());
+
+    let log_path = output_log.map(|(path, file)| {
+        let file = Arc::new(Mutex::new(file));
+        if let Some(stdout) = child.stdout.take() {
+            process::capture_output(stdout, "stdout", file.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            process::capture_output(stderr, "stderr", file);
+        }
+        path.to_string_lossy().to_string()
+    });
+
+    let running = RunningGame {
+        pid: child.id(),
+        profile_id: profile.id.clone(),
+        game_dir: game_dir.clone(),
+        started_at: chrono::Local::now().to_rfc3339(),
+        log_path,
+    };
+    games.insert(running.clone());
+    if let Err(e) = app.emit(process::GAME_STARTED_EVENT, &running) {
+        tracing::warn!("发送游戏启动事件失败: {}", e);
+    }
+
+    // 等待游戏退出：移除记录、发送退出事件并执行退出后命令
+    let started = std::time::Instant::now();
+    let pid = running.pid;
+    std::thread::spawn(move || {
+        let status = child.wait();
+        let game = app.state::<GameProcesses>().remove(pid);
+        let exit_code = status.as_ref().ok().and_then(|s| s.code());
+        let success = status.as_ref().is_ok_and(|s| s.success());
+        let duration_secs = started.elapsed().as_secs_f64();
+        if success {
+            tracing::info!("游戏已退出 (PID {})，运行 {:.0} 秒", pid, duration_secs);
+        } else {
+            tracing::warn!("游戏异常退出 (PID {})，退出码: {:?}，状态: {:?}", pid, exit_code, status);
+        }
+
+        if let Some(game) = game {
+            let event = GameExit { game, exit_code, success, duration_secs };
+            if let Err(e) = app.emit(process::GAME_EXITED_EVENT, &event) {
+                tracing::warn!("发送游戏退出事件失败: {}", e);
+            }
+        }
+
+        if let Some(hook) = profile.post_exit.as_deref().filter(|h| !h.trim().is_empty()) {
+            tracing::info!("执行退出后命令: {}", hook);
+            match hook_command(hook, &profile, &working_dir).and_then(|mut c| c.status().map_err(|e| e.to_string())) {
+                Ok(status) if status.success() => {}
+                Ok(status) => tracing::warn!("退出后命令失败: {}", status),
+                Err(e) => tracing::warn!("执行退出后命令失败: {}", e),
+            }
+        }
+    });
+
+    Ok(running)
+}
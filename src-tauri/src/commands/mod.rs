@@ -1,11 +1,18 @@
 // 模块声明
 pub mod checksum;
+pub mod diagnostics;
 pub mod file_system;
+pub mod launch;
 pub mod network;
+pub mod preview;
+pub mod settings;
+pub mod skin;
+pub mod thumbnail;
 pub mod zip;
 
 // 重新导出所有 Tauri 命令，方便在 lib.rs 中统一注册
 pub use checksum::{calculate_checksums, save_checksums_to_file};
+pub use diagnostics::{collect_game_logs, get_log_level, get_recent_logs, set_log_level, set_log_stream};
 pub use file_system::{
     get_app_exe_path, 
     get_app_exe_folder_path, 
@@ -13,8 +20,6 @@
     greet, 
     list_bat_files, 
     execute_bat_file, 
-    get_launch_options, 
-    launch_game,
     list_chart_categories,
     list_charts_in_category,
     delete_chart,
@@ -24,5 +29,10 @@
     list_skins,
     delete_skin
 };
-pub use network::{download_file_to_path, fetch_remote_hashes, fetch_chart_list, fetch_github_skins, download_skin_zip, download_charts_batch, clear_api_cache};
+pub use launch::{get_launch_options, save_launch_profile, delete_launch_profile, reset_launch_profiles, launch_game, get_running_games};
+pub use network::{download_file_to_path, fetch_remote_hashes, fetch_chart_list, fetch_chart_detail, fetch_github_skins, download_skin_zip, download_charts_batch, clear_api_cache};
+pub use preview::preview_chart_track;
+pub use settings::{get_settings, save_settings, reset_settings, import_legacy_settings, get_game_paths};
+pub use skin::{get_active_skin, set_active_skin, check_skin_updates, validate_skin, compose_skin};
+pub use thumbnail::{prefetch_chart_thumbnails, clear_thumbnail_cache};
 pub use zip::{extract_zip, download_and_extract};
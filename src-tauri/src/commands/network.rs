@@ -1,82 +1,32 @@
 use std::path::Path;
-use std::collections::HashMap;
-use std::sync::{OnceLock, Mutex};
 use tokio::fs;
-use crate::models::FileChecksum;
-use serde::{Deserialize, Serialize};
-use tauri::Emitter;
+use crate::models::{FileChecksum, ChartMetadata, SkinInstallRecord, CHART_METADATA_FILE, SKIN_INSTALL_FILE};
+use crate::utils::{sanitize_file_name, detect_extension, MediaKind};
+use crate::api::cache::{self, CachePolicy};
+use crate::api::majdata::{parse_designers, ChartInteract, CHART_LIST_PAGE_SIZE};
+use crate::api::{ChartDetail, ChartFileSizes, ChartListPage, ChartListQuery, ChartSummary, GithubSkin, HttpClient, MajdataClient, SkinSource};
+use crate::commands::skin::{log_validation, skin_dir, validate_skin_dir, SkinValidation};
+use serde::Serialize;
+use tauri::{Emitter, State};
+use crate::launcher::guard::{self, GuardedError};
+use crate::launcher::GameProcesses;
+use crate::settings::SettingsStore;
 
-/// API 响应缓存（URL → 响应体文本）
-static API_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
-
-fn get_cache() -> &'static Mutex<HashMap<String, String>> {
-    API_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
-}
-
-/// Tauri命令：清除所有 API 缓存
+/// Tauri命令：清除所有 API 缓存（内存和磁盘）
 #[tauri::command]
 pub fn clear_api_cache() -> Result<(), String> {
-    let mut cache = get_cache()
+    let count = cache::get_cache()
         .lock()
-        .map_err(|e| format!("锁定缓存失败: {}", e))?;
-    let count = cache.len();
-    cache.clear();
+        .map_err(|e| format!("锁定缓存失败: {}", e))?
+        .clear();
     tracing::info!("已清除 API 缓存，共 {} 条记录", count);
     Ok(())
 }
 
-/// 谱面摘要信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChartSummary {
-    pub id: String,
-    pub title: String,
-    pub artist: String,
-    pub designer: String,
-    pub uploader: String,
-    pub levels: Vec<Option<String>>,  // API 返回数组，可能包含 null、空字符串或 "13+", "14" 等
-}
-
-/// GitHub 文件信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GithubSkin {
-    pub name: String,
-    pub download_url: String,
-    pub size: u64,
-}
-
-/// 创建 HTTP 客户端，支持代理和重定向
-pub fn create_http_client(proxy: Option<String>) -> Result<reqwest::Client, String> {
-    let mut client_builder = reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))  // 支持最多10次重定向
-        .timeout(std::time::Duration::from_secs(300));  // 5分钟超时
-    
-    if let Some(proxy_url) = proxy {
-        if !proxy_url.is_empty() {
-            let proxy = reqwest::Proxy::all(&proxy_url)
-                .map_err(|e| {
-                    tracing::error!("无效的代理 URL: {}", e);
-                    format!("Invalid proxy URL: {}", e)
-                })?;
-            client_builder = client_builder.proxy(proxy);
-        }
-    }
-    
-    client_builder.build()
-        .map_err(|e| {
-            tracing::error!("创建 HTTP 客户端失败: {}", e);
-            format!("Failed to create HTTP client: {}", e)
-        })
-}
-
-/// 下载文件到指定路径（内部共享函数）
-/// 被 download_file_to_path 和 download_and_extract 复用
-pub async fn download_file_impl(url: String, output_path: String, proxy: Option<String>) -> Result<String, String> {
-    tracing::info!("下载文件: {} -> {}", url, output_path);
-    let start_time = std::time::Instant::now();
-    
-    let client = create_http_client(proxy)?;
-    
-    let response = client.get(&url)
+/// 下载文件内容到内存（内部共享函数）
+/// 返回响应体和 Content-Type，供需要识别文件类型的调用方使用
+pub async fn fetch_bytes_impl(client: &reqwest::Client, url: &str) -> Result<(Vec<u8>, Option<String>), String> {
+    let response = client.get(url)
         .send()
         .await
         .map_err(|e| {
@@ -94,6 +44,11 @@ pub async fn download_file_impl(url: String, output_path: String, proxy: Option<
         return Err(format!("Download failed with status: {}", status));
     }
     
+    let content_type = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    
     let bytes = response.bytes()
         .await
         .map_err(|e| {
@@ -101,6 +56,17 @@ pub async fn download_file_impl(url: String, output_path: String, proxy: Option<
             format!("Failed to read response: {}", e)
         })?;
     
+    Ok((bytes.to_vec(), content_type))
+}
+
+/// 下载文件到指定路径（内部共享函数）
+/// 被 download_file_to_path 和 download_and_extract 复用
+pub async fn download_file_impl(client: &reqwest::Client, url: String, output_path: String) -> Result<String, String> {
+    tracing::info!("下载文件: {} -> {}", url, output_path);
+    let start_time = std::time::Instant::now();
+    
+    let (bytes, _) = fetch_bytes_impl(client, &url).await?;
+    
     let file_size = bytes.len();
     
     fs::write(&output_path, &bytes)
@@ -116,11 +82,53 @@ pub async fn download_file_impl(url: String, output_path: String, proxy: Option<
     Ok(format!("Downloaded to {}", output_path))
 }
 
+/// 下载谱面资源文件，根据实际内容决定扩展名
+///
+/// `stem` 为不含扩展名的文件名（如 `bg`、`track`）。写入成功后会删除同名但扩展名不同的旧文件，
+/// 避免文件夹中同时存在 bg.jpg 和 bg.png。返回实际写入的文件名。
+async fn download_media_file(
+    client: &MajdataClient,
+    url: &str,
+    folder: &Path,
+    stem: &str,
+    kind: MediaKind,
+) -> Result<String, String> {
+    let (bytes, content_type) = client.get_bytes(url).await?;
+    let ext = detect_extension(kind, content_type.as_deref(), &bytes)?;
+    let file_name = format!("{}.{}", stem, ext);
+    
+    fs::write(folder.join(&file_name), &bytes)
+        .await
+        .map_err(|e| {
+            tracing::error!("❌ 写入文件失败: {}", e);
+            format!("Failed to write file: {}", e)
+        })?;
+    
+    for other_ext in kind.extensions().iter().filter(|e| **e != ext) {
+        let stale = folder.join(format!("{}.{}", stem, other_ext));
+        if stale.exists() {
+            tracing::debug!("删除旧文件: {:?}", stale);
+            let _ = fs::remove_file(&stale).await;
+        }
+    }
+    
+    Ok(file_name)
+}
+
 /// Tauri命令：下载单个文件到指定位置
 /// 复用 download_file_impl，添加父目录创建逻辑
 #[tauri::command]
-pub async fn download_file_to_path(url: String, file_path: String, target_dir: String, proxy: Option<String>) -> Result<String, String> {
+pub async fn download_file_to_path(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    url: String,
+    file_path: String,
+    target_dir: String
+) -> Result<String, GuardedError> {
+    let client = http.get(&settings.get())?;
     let full_path = Path::new(&target_dir).join(&file_path);
+    guard::ensure_not_running(&games, &full_path)?;
     
     // 确保父目录存在
     if let Some(parent) = full_path.parent() {
@@ -133,42 +141,29 @@ pub async fn download_file_to_path(url: String, file_path: String, target_dir: S
     }
     
     // 复用基础下载函数
-    download_file_impl(url, full_path.to_string_lossy().to_string(), proxy).await
+    Ok(download_file_impl(&client, url, full_path.to_string_lossy().to_string()).await?)
 }
 
 /// Tauri命令：获取远程哈希文件
+/// 经过 API 缓存，清单未变化时服务器返回 304，不重复下载
 #[tauri::command]
-pub async fn fetch_remote_hashes(url: String, proxy: Option<String>) -> Result<Vec<FileChecksum>, String> {
+pub async fn fetch_remote_hashes(
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    url: String
+) -> Result<Vec<FileChecksum>, String> {
     tracing::info!("获取远程哈希: {}", url);
     let start_time = std::time::Instant::now();
     
-    let client = create_http_client(proxy.clone())?;
+    let client = http.get(&settings.get())?;
     
-    let response = client.get(&url)
-        .send()
+    let hashes: Vec<FileChecksum> = cache::get_json_cached(client.get(&url), &url, CachePolicy::REMOTE_HASHES)
         .await
         .map_err(|e| {
-            tracing::error!("❌ 获取远程哈希失败!");
-            tracing::error!("  错误类型: {}", e);
-            tracing::error!("  是否超时: {}", e.is_timeout());
-            tracing::error!("  是否连接错误: {}", e.is_connect());
+            tracing::error!("❌ 获取远程哈希失败: {}", e);
             format!("Failed to fetch remote hashes: {}", e)
         })?;
     
-    let status = response.status();
-    
-    if !status.is_success() {
-        tracing::error!("获取远程哈希失败，HTTP 状态码: {}", status);
-        return Err(format!("Failed to fetch remote hashes with status: {}", status));
-    }
-    
-    let hashes: Vec<FileChecksum> = response.json()
-        .await
-        .map_err(|e| {
-            tracing::error!("❌ 解析哈希 JSON 失败: {}", e);
-            format!("Failed to parse remote hashes JSON: {}", e)
-        })?;
-    
     let elapsed = start_time.elapsed();
     tracing::info!("获取远程哈希成功: {} 个文件, {:.2}s", hashes.len(), elapsed.as_secs_f64());
     
@@ -178,191 +173,207 @@ pub async fn fetch_remote_hashes(url: String, proxy: Option<String>) -> Result<V
 /// Tauri命令：搜索谱面列表
 #[tauri::command]
 pub async fn fetch_chart_list(
-    search: String,
-    sort_type: i32,
-    page: i32,
-    proxy: Option<String>
-) -> Result<Vec<ChartSummary>, String> {
-    tracing::info!("搜索谱面: '{}', sort={}, page={}", search, sort_type, page);
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    query: ChartListQuery
+) -> Result<ChartListPage, String> {
+    tracing::info!("搜索谱面: '{}', sort={:?}, page={}, filter={:?}", query.search, query.sort, query.page, query.filter);
     let start_time = std::time::Instant::now();
     
-    let client = create_http_client(proxy.clone())?;
-    
-    // 构建 API URL
-    let sort_words = ["", "likep", "commp", "playp"];
-    let sort_param = if sort_type >= 0 && sort_type < sort_words.len() as i32 {
-        sort_words[sort_type as usize]
-    } else {
-        ""
-    };
-    
-    let url = format!(
-        "https://majdata.net/api3/api/maichart/list?sort={}&page={}&search={}",
-        sort_param,
-        page,
-        urlencoding::encode(&search)
-    );
+    let settings = settings.get();
+    let client = MajdataClient::new(settings.api_base(), http.get(&settings)?)?;
+    let url = client.chart_list_url(&query);
 
-    // 先查缓存
-    {
-        let cache = get_cache()
-            .lock()
-            .map_err(|e| format!("锁定缓存失败: {}", e))?;
-        if let Some(cached_text) = cache.get(&url) {
-            tracing::info!("[缓存命中] {}", url);
-            let charts: Vec<ChartSummary> = serde_json::from_str(cached_text)
-                .map_err(|e| format!("解析缓存 JSON 失败: {}", e))?;
-            return Ok(charts);
-        }
-    }
-    
-    let response = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| {
-            tracing::error!("❌ 网络请求失败!");
-            tracing::error!("  错误类型: {}", e);
-            tracing::error!("  是否超时: {}", e.is_timeout());
-            tracing::error!("  是否连接错误: {}", e.is_connect());
-            format!("Failed to fetch chart list: {}", e)
-        })?;
+    // 缓存保存服务器原始响应，过滤条件在解析后应用
+    let charts: Vec<ChartSummary> = client.get_json_cached(&url, CachePolicy::CHART_LIST).await?;
     
-    let status = response.status();
+    let fetched = charts.len();
+    let has_more = fetched >= CHART_LIST_PAGE_SIZE;
+    let charts = query.filter.apply(charts)?;
     
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_else(|_| "无法读取错误响应体".to_string());
-        tracing::error!("❌ HTTP 请求失败!");
-        tracing::error!("  状态码: {}", status);
-        tracing::error!("  响应体: {}", error_body);
-        return Err(format!("Failed to fetch chart list with status: {} - {}", status, error_body));
-    }
+    let elapsed = start_time.elapsed();
+    tracing::info!("获取谱面列表成功: {} 个谱面（过滤后 {} 个）, {:.2}s", fetched, charts.len(), elapsed.as_secs_f64());
     
-    // 读取响应体文本
-    let response_text = response.text()
-        .await
-        .map_err(|e| {
-            tracing::error!("❌ 读取响应体失败: {}", e);
-            format!("Failed to read response body: {}", e)
-        })?;
+    Ok(ChartListPage { charts, has_more })
+}
+
+/// Tauri命令：获取谱面详细信息（点赞/播放/评论数、标签、简介、各难度谱师、文件大小）
+///
+/// 摘要和互动信息都是必需的，任一请求失败或格式不符时返回错误；谱师和文件大小获取失败时为空。
+#[tauri::command]
+pub async fn fetch_chart_detail(
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    chart_id: String
+) -> Result<ChartDetail, String> {
+    tracing::info!("获取谱面详情: {}", chart_id);
+    let start_time = std::time::Instant::now();
     
-    // 解析 JSON
-    let charts: Vec<ChartSummary> = serde_json::from_str(&response_text)
-        .map_err(|e| {
-            tracing::error!("❌ 解析 JSON 失败!");
-            tracing::error!("  错误: {}", e);
-            tracing::error!("  响应体（前 1000 字符）: {}", 
-                if response_text.len() > 1000 { 
-                    &response_text[..1000] 
-                } else { 
-                    &response_text 
+    let settings = settings.get();
+    let client = MajdataClient::new(settings.api_base(), http.get(&settings)?)?;
+    let url = client.chart_detail_url(&chart_id);
+    
+    let mut detail: ChartDetail = client.get_json_cached(&url, CachePolicy::CHART_DETAIL).await?;
+    let interact: ChartInteract = client
+        .get_json_cached(&client.chart_interact_url(&chart_id), CachePolicy::CHART_DETAIL)
+        .await?;
+    detail.likes = interact.like_list;
+    detail.plays = interact.plays;
+    detail.comments = interact.comments_list;
+    
+    // 各难度谱师从 maidata.txt 中读取，单独缓存解析结果
+    let designers_key = format!("{}#designers", url);
+    let cached_designers = cache::get_local(&designers_key)
+        .and_then(|text| serde_json::from_str::<Vec<Option<String>>>(&text).ok());
+    detail.designers = match cached_designers {
+        Some(designers) => designers,
+        None => match client.get_bytes(&client.chart_url(&chart_id)).await {
+            Ok((bytes, _)) => {
+                let designers = parse_designers(&String::from_utf8_lossy(&bytes), detail.summary.levels.len());
+                if let Ok(json) = serde_json::to_string(&designers) {
+                    cache::put_local(&designers_key, json, CachePolicy::CHART_DETAIL.ttl);
                 }
-            );
-            format!("Failed to parse chart list JSON: {}", e)
-        })?;
-
-    // 存入缓存
-    {
-        let mut cache = get_cache()
-            .lock()
-            .map_err(|e| format!("锁定缓存失败: {}", e))?;
-        cache.insert(url.clone(), response_text);
-        tracing::info!("[缓存存储] {} （当前缓存条数: {}）", url, cache.len());
-    }
+                designers
+            }
+            Err(e) => {
+                tracing::warn!("获取谱面文件失败，无法读取各难度谱师: {}", e);
+                Vec::new()
+            }
+        },
+    };
+    
+    // 文件大小通过 HEAD 请求获得，单独缓存
+    let sizes_key = format!("{}#file_sizes", url);
+    let cached_sizes = cache::get_local(&sizes_key)
+        .and_then(|text| serde_json::from_str::<ChartFileSizes>(&text).ok());
+    detail.file_sizes = match cached_sizes {
+        Some(sizes) => sizes,
+        None => {
+            let sizes = client.chart_file_sizes(&chart_id).await;
+            if let Ok(json) = serde_json::to_string(&sizes) {
+                cache::put_local(&sizes_key, json, CachePolicy::CHART_DETAIL.ttl);
+            }
+            sizes
+        }
+    };
     
     let elapsed = start_time.elapsed();
-    tracing::info!("获取谱面列表成功: {} 个谱面, {:.2}s", charts.len(), elapsed.as_secs_f64());
+    tracing::info!("获取谱面详情成功: {}, {:.2}s", detail.summary.title, elapsed.as_secs_f64());
     
-    Ok(charts)
+    Ok(detail)
 }
 
-/// Tauri命令：获取 GitHub 仓库中的皮肤列表
+/// Tauri命令：获取所有皮肤来源中的皮肤列表
+///
+/// 使用设置中的皮肤来源，未配置时使用官方仓库 teamMajdata/MajdataPlay-Skins。
 #[tauri::command]
-pub async fn fetch_github_skins(proxy: Option<String>) -> Result<Vec<GithubSkin>, String> {
-    tracing::info!("获取 GitHub 皮肤列表");
+pub async fn fetch_github_skins(
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>
+) -> Result<Vec<GithubSkin>, String> {
+    let settings = settings.get();
+    fetch_skins_from_sources(settings.skin_sources(), &http.get(&settings)?).await
+}
+
+/// 获取皮肤来源中的皮肤列表
+///
+/// 结果按来源优先级（高在前）合并，每个皮肤标记来源名称；单个来源失败不影响其他来源。
+pub async fn fetch_skins_from_sources(mut sources: Vec<SkinSource>, client: &reqwest::Client) -> Result<Vec<GithubSkin>, String> {
+    sources.sort_by_key(|s| std::cmp::Reverse(s.priority));
+    tracing::info!("获取皮肤列表: {} 个来源", sources.len());
     let start_time = std::time::Instant::now();
     
-    let client = create_http_client(proxy)?;
-    
-    let url = "https://api.github.com/repos/teamMajdata/MajdataPlay-Skins/contents/";
-    
-    let response = client.get(url)
-        .header("User-Agent", "majdata-hub")
-        .send()
-        .await
-        .map_err(|e| {
-            tracing::error!("❌ 请求 GitHub API 失败!");
-            tracing::error!("  错误类型: {}", e);
-            tracing::error!("  是否超时: {}", e.is_timeout());
-            tracing::error!("  是否连接错误: {}", e.is_connect());
-            format!("Failed to fetch GitHub skins: {}", e)
-        })?;
-    
-    let status = response.status();
+    let results = futures_util::future::join_all(sources.iter().map(|source| source.fetch(client))).await;
     
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_else(|_| "无法读取错误响应体".to_string());
-        tracing::error!("❌ GitHub API 请求失败!");
-        tracing::error!("  状态码: {}", status);
-        tracing::error!("  响应体: {}", error_body);
-        return Err(format!("Failed to fetch GitHub skins with status: {} - {}", status, error_body));
+    let mut skins: Vec<GithubSkin> = Vec::new();
+    let mut first_error = None;
+    for (source, result) in sources.iter().zip(results) {
+        match result {
+            Ok(list) => {
+                tracing::info!("  ✓ {}: {} 个文件", source.name, list.len());
+                skins.extend(list);
+            }
+            Err(e) => {
+                tracing::error!("  ✗ 皮肤来源 {} 获取失败: {}", source.name, e);
+                first_error.get_or_insert(format!("{}: {}", source.name, e));
+            }
+        }
     }
     
-    // 读取响应体文本
-    let response_text = response.text()
-        .await
-        .map_err(|e| {
-            tracing::error!("❌ 读取响应体失败: {}", e);
-            format!("Failed to read response body: {}", e)
-        })?;
-    
-    // GitHub API 返回的是一个数组，每个元素包含 name, download_url, size 等字段
-    #[derive(Deserialize)]
-    struct GithubFile {
-        name: String,
-        download_url: Option<String>,
-        size: u64,
+    // 所有来源都失败时返回错误
+    if skins.is_empty() {
+        if let Some(e) = first_error {
+            return Err(format!("Failed to fetch skins: {}", e));
+        }
     }
     
-    let files: Vec<GithubFile> = serde_json::from_str(&response_text)
-        .map_err(|e| {
-            tracing::error!("❌ 解析 GitHub API JSON 失败!");
-            tracing::error!("  错误: {}", e);
-            tracing::error!("  响应体（前 1000 字符）: {}", 
-                if response_text.len() > 1000 { 
-                    &response_text[..1000] 
-                } else { 
-                    &response_text 
-                }
-            );
-            format!("Failed to parse GitHub API JSON: {}", e)
-        })?;
-    
-    // 只保留有 download_url 的文件
-    let skins: Vec<GithubSkin> = files.into_iter()
-        .filter_map(|f| {
-            f.download_url.map(|url| GithubSkin {
-                name: f.name,
-                download_url: url,
-                size: f.size,
-            })
-        })
-        .collect();
-    
     let elapsed = start_time.elapsed();
-    tracing::info!("获取 GitHub 皮肤列表成功: {} 个文件, {:.2}s", skins.len(), elapsed.as_secs_f64());
+    tracing::info!("获取皮肤列表成功: {} 个文件, {:.2}s", skins.len(), elapsed.as_secs_f64());
     
     Ok(skins)
 }
 
-/// Tauri命令：下载皮肤 ZIP 文件并解压
+/// 皮肤备份目录名（与 Skins 目录同级，避免被识别为皮肤）
+const SKIN_BACKUP_DIR_NAME: &str = "SkinBackups";
+
+/// 每个皮肤保留的备份数量
+const MAX_SKIN_BACKUPS: usize = 3;
+
+/// 备份文件夹名中的时间格式
+const SKIN_BACKUP_TIME_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// 删除某个皮肤多余的旧备份，只保留最近 `MAX_SKIN_BACKUPS` 个
+///
+/// 备份文件夹名为 `<皮肤名>_<时间>`，时间部分必须是 14 位数字，避免误删名称相近的其他皮肤的备份。
+async fn prune_skin_backups(backup_root: &Path, skin_folder_name: &str) {
+    let Ok(mut entries) = fs::read_dir(backup_root).await else {
+        return;
+    };
+    let prefix = format!("{}_", skin_folder_name);
+    let mut backups = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_backup = name
+            .strip_prefix(&prefix)
+            .is_some_and(|time| time.len() == 14 && time.bytes().all(|b| b.is_ascii_digit()));
+        if is_backup {
+            backups.push((name, entry.path()));
+        }
+    }
+
+    // 时间部分定长，按名称排序即按时间排序
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    for (name, path) in backups.into_iter().skip(MAX_SKIN_BACKUPS) {
+        match fs::remove_dir_all(&path).await {
+            Ok(()) => tracing::info!("删除旧的皮肤备份: {}", name),
+            Err(e) => tracing::warn!("删除旧的皮肤备份失败 {}: {}", name, e),
+        }
+    }
+}
+
+/// Tauri命令：下载皮肤 ZIP 文件并解压到设置中游戏目录下的 Skins
+///
+/// `skin` 为 `fetch_github_skins` 返回的条目，其 SHA、大小和来源写入安装记录用于检测更新。
+/// 同名皮肤已存在时原地升级：旧版本移动到 SkinBackups 目录后再替换，每个皮肤只保留最近几个备份。
+/// 安装后自动校验皮肤结构并返回校验结果。
 #[tauri::command]
 pub async fn download_skin_zip(
-    url: String,
-    skin_name: String,
-    skins_dir: String,
-    proxy: Option<String>
-) -> Result<String, String> {
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    skin: GithubSkin
+) -> Result<SkinValidation, GuardedError> {
+    let settings = settings.get();
+    let skins_dir = settings.skins_dir();
+    let GithubSkin { name: skin_name, download_url: url, sha, size, source, .. } = skin;
     tracing::info!("下载并解压皮肤: {} -> {}", url, skin_name);
+    if sanitize_file_name(&skin_name).as_deref() != Some(skin_name.as_str()) {
+        return Err(format!("无效的皮肤名称: {}", skin_name).into());
+    }
+    // 目标文件夹（去掉一个 .zip 后缀），同样必须是有效的文件夹名：`.zip`、`..zip` 会得到 Skins 目录本身或其上级
+    let skin_folder_name = skin_name.strip_suffix(".zip").unwrap_or(&skin_name);
+    let target_dir = skin_dir(&skins_dir, skin_folder_name)?;
+    guard::ensure_not_running(&games, Path::new(&skins_dir))?;
     
     // 确保 Skins 目录存在
     tokio::fs::create_dir_all(&skins_dir)
@@ -375,19 +386,39 @@ pub async fn download_skin_zip(
     // 临时 ZIP 文件路径
     let temp_zip_path = Path::new(&skins_dir).join("temp_skin.zip");
     
-    // 下载文件
-    download_file_impl(url, temp_zip_path.to_string_lossy().to_string(), proxy).await?;
-    
-    // 创建目标文件夹（去掉 .zip 后缀）
-    let skin_folder_name = skin_name.trim_end_matches(".zip");
-    let target_dir = Path::new(&skins_dir).join(skin_folder_name);
+    // 下载文件（本地文件夹来源的 url 为 zip 文件路径，直接复制）
+    if url.starts_with("http://") || url.starts_with("https://") {
+        let client = http.get(&settings)?;
+        download_file_impl(&client, url.clone(), temp_zip_path.to_string_lossy().to_string()).await?;
+    } else {
+        // 只允许复制已配置的本地皮肤来源文件夹中的 zip，不接受前端传入的任意路径
+        let local_zip = settings.skin_sources()
+            .iter()
+            .filter(|s| s.name == source)
+            .find_map(|s| s.resolve_local_zip(&url))
+            .ok_or_else(|| {
+                tracing::error!("本地皮肤文件不属于来源 {}: {}", source, url);
+                format!("本地皮肤文件不在已配置的本地皮肤来源中: {}", url)
+            })?;
+        tokio::fs::copy(&local_zip, &temp_zip_path)
+            .await
+            .map_err(|e| {
+                tracing::error!("复制本地皮肤文件失败: {}", e);
+                format!("Failed to copy local skin file: {}", e)
+            })?;
+    }
+    let zip_size = fs::metadata(&temp_zip_path).await.map(|m| m.len()).unwrap_or(0);
     
-    // 解压到该文件夹
+    // 先解压到临时文件夹，成功后再替换，避免解压失败破坏已安装的版本
+    let staging_dir = Path::new(&skins_dir).join(format!(".{}.partial", skin_folder_name));
+    if staging_dir.exists() {
+        let _ = fs::remove_dir_all(&staging_dir).await;
+    }
     tracing::info!("开始解压皮肤到: {:?}", target_dir);
-    let extract_result = crate::commands::zip::extract_zip(
+    let extract_result = crate::commands::zip::extract_zip_impl(
         temp_zip_path.to_string_lossy().to_string(),
-        target_dir.to_string_lossy().to_string()
-    )?;
+        staging_dir.to_string_lossy().to_string()
+    );
     
     // 删除临时 ZIP 文件
     tracing::debug!("删除临时 ZIP 文件");
@@ -398,8 +429,64 @@ pub async fn download_skin_zip(
             format!("Failed to remove temp zip file: {}", e)
         })?;
     
+    if let Err(e) = extract_result {
+        let _ = fs::remove_dir_all(&staging_dir).await;
+        return Err(e.into());
+    }
+    
+    let record = SkinInstallRecord {
+        file_name: skin_name.clone(),
+        source,
+        download_url: url,
+        sha,
+        size: if size > 0 { size } else { zip_size },
+        installed_at: chrono::Local::now().to_rfc3339(),
+    };
+    let record_json = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("Failed to serialize skin record: {}", e))?;
+    if let Err(e) = fs::write(staging_dir.join(SKIN_INSTALL_FILE), record_json).await {
+        tracing::warn!("写入皮肤安装记录失败: {}", e);
+    }
+    
+    // 已安装旧版本时先备份
+    let backup_root = Path::new(&skins_dir).with_file_name(SKIN_BACKUP_DIR_NAME);
+    let backed_up = target_dir.exists();
+    if backed_up {
+        let backup_dir = backup_root.join(format!(
+            "{}_{}",
+            skin_folder_name,
+            chrono::Local::now().format(SKIN_BACKUP_TIME_FORMAT)
+        ));
+        fs::create_dir_all(&backup_root)
+            .await
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        fs::rename(&target_dir, &backup_dir)
+            .await
+            .map_err(|e| {
+                tracing::error!("备份旧版本皮肤失败: {}", e);
+                format!("Failed to back up previous skin: {}", e)
+            })?;
+        tracing::info!("旧版本皮肤已备份到: {:?}", backup_dir);
+    }
+    
+    fs::rename(&staging_dir, &target_dir)
+        .await
+        .map_err(|e| {
+            tracing::error!("替换皮肤文件夹失败: {}", e);
+            format!("Failed to install skin: {}", e)
+        })?;
+    
+    if backed_up {
+        prune_skin_backups(&backup_root, skin_folder_name).await;
+    }
     tracing::info!("皮肤下载并解压完成");
-    Ok(extract_result)
+    
+    let validation_dir = target_dir.clone();
+    let validation = tokio::task::spawn_blocking(move || validate_skin_dir(&validation_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    log_validation(skin_folder_name, &validation);
+    Ok(validation)
 }
 
 /// 下载进度事件
@@ -410,23 +497,68 @@ struct DownloadProgress {
     chart_title: String,
 }
 
-/// Tauri命令：批量下载谱面
+/// 读取谱面文件夹中的元数据 sidecar
+async fn read_chart_metadata(chart_folder: &Path) -> Option<ChartMetadata> {
+    let text = fs::read_to_string(chart_folder.join(CHART_METADATA_FILE)).await.ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// 为谱面选择文件夹名
+///
+/// 默认使用清理后的标题；若同名文件夹已被其他谱面占用（元数据中的 ID 不同或没有元数据），
+/// 则追加谱面 ID 前缀区分。对同一谱面多次下载总是得到同一个文件夹。
+async fn choose_chart_folder_name(category_dir: &Path, chart_id: &str, chart_title: &str) -> String {
+    let safe_id = sanitize_file_name(chart_id).unwrap_or_else(|| "chart".to_string());
+    let base_name = sanitize_file_name(chart_title).unwrap_or_else(|| safe_id.clone());
+
+    let base_folder = category_dir.join(&base_name);
+    if !base_folder.exists() {
+        return base_name;
+    }
+    match read_chart_metadata(&base_folder).await {
+        Some(meta) if meta.id == chart_id => base_name,
+        _ => {
+            let short_id: String = safe_id.chars().take(8).collect();
+            let folder_name = format!("{} [{}]", base_name, short_id);
+            tracing::debug!("文件夹 '{}' 已被占用，改用 '{}'", base_name, folder_name);
+            folder_name
+        }
+    }
+}
+
+/// 写入谱面元数据 sidecar
+async fn write_chart_metadata(chart_folder: &Path, metadata: &ChartMetadata) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(metadata)
+        .map_err(|e| format!("Failed to serialize chart metadata: {}", e))?;
+    fs::write(chart_folder.join(CHART_METADATA_FILE), json)
+        .await
+        .map_err(|e| format!("Failed to write chart metadata: {}", e))
+}
+
+/// Tauri命令：批量下载谱面到设置中游戏目录下 MaiCharts 的指定分类
+///
+/// 游戏运行时拒绝写入。
 #[tauri::command]
 pub async fn download_charts_batch(
     app: tauri::AppHandle,
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
     chart_ids: Vec<String>,
     chart_titles: Vec<String>,
-    maicharts_dir: String,
-    category: String,
-    proxy: Option<String>
-) -> Result<String, String> {
+    category: String
+) -> Result<String, GuardedError> {
+    let settings = settings.get();
+    let maicharts_dir = settings.maicharts_dir();
     tracing::info!("批量下载谱面: {} 个谱面到分类 '{}'", chart_ids.len(), category);
     
     if chart_ids.len() != chart_titles.len() {
-        return Err("谱面ID和标题数量不匹配".to_string());
+        return Err("谱面ID和标题数量不匹配".to_string().into());
     }
+    let category_dir = maicharts_dir.join(&category);
+    guard::ensure_not_running(&games, &category_dir)?;
     
-    let api_root = "https://majdata.net/api3/api";
+    let client = MajdataClient::new(settings.api_base(), http.get(&settings)?).map_err(String::from)?;
     let total = chart_ids.len();
     let mut success_count = 0;
     
@@ -440,10 +572,9 @@ pub async fn download_charts_batch(
             chart_title: chart_title.clone(),
         });
         
-        // 创建谱面文件夹路径
-        let chart_folder = Path::new(&maicharts_dir)
-            .join(&category)
-            .join(chart_title);
+        // 创建谱面文件夹路径（标题清理为安全的文件夹名）
+        let folder_name = choose_chart_folder_name(&category_dir, chart_id, chart_title).await;
+        let chart_folder = category_dir.join(&folder_name);
         
         // 确保文件夹存在
         if let Err(e) = tokio::fs::create_dir_all(&chart_folder).await {
@@ -451,41 +582,42 @@ pub async fn download_charts_batch(
             continue;
         }
         
-        // 下载文件列表
+        // 写入元数据，保留原始标题
+        let metadata = ChartMetadata {
+            id: chart_id.clone(),
+            title: chart_title.clone(),
+            folder_name: folder_name.clone(),
+            downloaded_at: chrono::Local::now().to_rfc3339(),
+        };
+        if let Err(e) = write_chart_metadata(&chart_folder, &metadata).await {
+            tracing::warn!("  ✗ 写入谱面元数据失败: {}", e);
+        }
+        
+        // 下载文件列表（扩展名根据实际内容决定）
         let files = vec![
-            ("track.mp3", format!("{}/maichart/{}/track", api_root, chart_id)),
-            ("bg.jpg", format!("{}/maichart/{}/image?fullImage=true", api_root, chart_id)),
-            ("maidata.txt", format!("{}/maichart/{}/chart", api_root, chart_id)),
+            ("track", MediaKind::Audio, client.track_url(chart_id)),
+            ("bg", MediaKind::Image, client.image_url(chart_id, true)),
+            ("maidata", MediaKind::Text, client.chart_url(chart_id)),
         ];
         
         let mut chart_success = true;
         
         // 下载基础文件
-        for (file_name, url) in files {
-            let file_path = chart_folder.join(file_name);
-            match download_file_impl(
-                url,
-                file_path.to_string_lossy().to_string(),
-                proxy.clone()
-            ).await {
-                Ok(_) => tracing::debug!("  ✓ {}", file_name),
+        for (stem, kind, url) in files {
+            match download_media_file(&client, &url, &chart_folder, stem, kind).await {
+                Ok(file_name) => tracing::debug!("  ✓ {}", file_name),
                 Err(e) => {
-                    tracing::warn!("  ✗ {} 下载失败: {}", file_name, e);
+                    tracing::warn!("  ✗ {} 下载失败: {}", stem, e);
                     chart_success = false;
                 }
             }
         }
         
         // 尝试下载视频（可选）
-        let video_url = format!("{}/maichart/{}/video", api_root, chart_id);
-        let video_path = chart_folder.join("pv.mp4");
-        match download_file_impl(
-            video_url,
-            video_path.to_string_lossy().to_string(),
-            proxy.clone()
-        ).await {
-            Ok(_) => tracing::debug!("  ✓ pv.mp4 (可选)"),
-            Err(_) => tracing::debug!("  - pv.mp4 不存在或下载失败（正常）"),
+        let video_url = client.video_url(chart_id);
+        match download_media_file(&client, &video_url, &chart_folder, "pv", MediaKind::Video).await {
+            Ok(file_name) => tracing::debug!("  ✓ {} (可选)", file_name),
+            Err(_) => tracing::debug!("  - pv 不存在或下载失败（正常）"),
         }
         
         if chart_success {
@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use tauri::{Manager, State};
+use crate::api::{HttpClient, MajdataClient};
+use crate::settings::SettingsStore;
+use crate::utils::media::AUDIO_EXTENSIONS;
+use crate::utils::{detect_extension, disk_cache, sanitize_file_name, MediaKind};
+
+/// 试听片段目录名（位于应用缓存目录）
+const PREVIEW_DIR_NAME: &str = "previews";
+
+/// 默认试听时长（秒）
+const DEFAULT_PREVIEW_SECONDS: u32 = 30;
+
+/// 最长试听时长（秒）
+const MAX_PREVIEW_SECONDS: u32 = 120;
+
+/// 按 320 kbps 估算每秒字节数，保证常见码率下至少能播放指定时长
+const BYTES_PER_SECOND: usize = 320 * 1000 / 8;
+
+/// 试听片段目录最多占用的字节数
+const PREVIEW_CACHE_MAX_BYTES: u64 = 128 * 1024 * 1024;
+
+/// 查找已缓存的试听片段（扩展名由实际格式决定）
+fn find_cached_preview(dir: &Path, stem: &str) -> Option<PathBuf> {
+    AUDIO_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .find(|path| path.exists())
+}
+
+/// Tauri命令：获取谱面音频的试听片段
+///
+/// 只下载 `/maichart/{id}/track` 的前若干秒，缓存到应用缓存目录并返回本地路径，
+/// 前端通过 asset 协议（`convertFileSrc`）播放。
+#[tauri::command]
+pub async fn preview_chart_track(
+    app: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    chart_id: String,
+    seconds: Option<u32>
+) -> Result<String, String> {
+    let seconds = seconds.unwrap_or(DEFAULT_PREVIEW_SECONDS).clamp(1, MAX_PREVIEW_SECONDS);
+    tracing::info!("获取试听片段: {} ({} 秒)", chart_id, seconds);
+    let start_time = std::time::Instant::now();
+
+    let dir = app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join(PREVIEW_DIR_NAME))
+        .map_err(|e| format!("Failed to get app cache directory: {}", e))?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| {
+            tracing::error!("创建试听目录失败: {}", e);
+            format!("Failed to create preview directory: {}", e)
+        })?;
+
+    let safe_id = sanitize_file_name(&chart_id)
+        .ok_or_else(|| format!("无效的谱面 ID: {}", chart_id))?;
+    let stem = format!("{}_{}s", safe_id, seconds);
+
+    if let Some(path) = find_cached_preview(&dir, &stem) {
+        tracing::info!("[缓存命中] 试听片段: {:?}", path);
+        disk_cache::touch(&path);
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    let settings = settings.get();
+    let client = MajdataClient::new(settings.api_base(), http.get(&settings)?)?;
+    let max_bytes = seconds as usize * BYTES_PER_SECOND;
+    let (bytes, content_type) = client.get_prefix(&client.track_url(&chart_id), max_bytes).await?;
+    let ext = detect_extension(MediaKind::Audio, content_type.as_deref(), &bytes)?;
+
+    // 先写临时文件再重命名，写入中断时不会留下被当作缓存的不完整文件
+    let path = dir.join(format!("{}.{}", stem, ext));
+    let temp_path = path.with_extension(format!("{}.part", ext));
+    let written = match tokio::fs::write(&temp_path, &bytes).await {
+        Ok(()) => tokio::fs::rename(&temp_path, &path).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = written {
+        tracing::error!("写入试听片段失败: {}", e);
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(format!("Failed to write preview: {}", e));
+    }
+
+    let evict_dir = dir.clone();
+    tokio::task::spawn_blocking(move || disk_cache::evict_lru_files(&evict_dir, PREVIEW_CACHE_MAX_BYTES));
+
+    let elapsed = start_time.elapsed();
+    tracing::info!("试听片段已缓存: {:?} ({:.2} KB, {:.2}s)", path, bytes.len() as f64 / 1024.0, elapsed.as_secs_f64());
+
+    Ok(path.to_string_lossy().to_string())
+}
@@ -0,0 +1,60 @@
+use serde::Serialize;
+use tauri::State;
+use crate::settings::{self, Settings, SettingsStore};
+
+/// 根据设置得到的游戏相关目录
+#[derive(Debug, Clone, Serialize)]
+pub struct GamePaths {
+    pub game_dir: String,
+    pub maicharts_dir: String,
+    pub skins_dir: String,
+}
+
+/// Tauri命令：获取应用设置
+#[tauri::command]
+pub fn get_settings(settings: State<'_, SettingsStore>) -> Settings {
+    settings.get()
+}
+
+/// Tauri命令：保存应用设置，返回保存后的设置
+#[tauri::command]
+pub fn save_settings(settings: State<'_, SettingsStore>, new_settings: Settings) -> Result<Settings, String> {
+    tracing::info!("保存设置");
+    settings.set(new_settings).inspect_err(|e| tracing::error!("保存设置失败: {}", e))
+}
+
+/// Tauri命令：恢复默认设置
+#[tauri::command]
+pub fn reset_settings(settings: State<'_, SettingsStore>) -> Result<Settings, String> {
+    tracing::info!("恢复默认设置");
+    settings.set(Settings::default())
+}
+
+/// Tauri命令：导入旧版前端保存在 localStorage 中的设置
+///
+/// `legacy` 为 `{ gamePath, httpProxy, launchRunner, skinSources }`，按版本 0 迁移后合并到当前设置。
+#[tauri::command]
+pub fn import_legacy_settings(
+    settings: State<'_, SettingsStore>,
+    legacy: serde_json::Value
+) -> Result<Settings, String> {
+    // 只记录字段名：代理地址中可能带有账号密码
+    let keys: Vec<&str> = legacy
+        .as_object()
+        .map(|object| object.iter().filter(|(_, v)| !v.is_null()).map(|(k, _)| k.as_str()).collect())
+        .unwrap_or_default();
+    tracing::info!("导入旧版设置，字段: {:?}", keys);
+    let merged = settings::merge_legacy(&settings.get(), legacy)?;
+    settings.set(merged)
+}
+
+/// Tauri命令：获取游戏目录、谱面目录和皮肤目录
+#[tauri::command]
+pub fn get_game_paths(settings: State<'_, SettingsStore>) -> GamePaths {
+    let settings = settings.get();
+    GamePaths {
+        game_dir: settings.game_dir().to_string_lossy().to_string(),
+        maicharts_dir: settings.maicharts_dir().to_string_lossy().to_string(),
+        skins_dir: settings.skins_dir().to_string_lossy().to_string(),
+    }
+}
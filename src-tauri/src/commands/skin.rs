@@ -0,0 +1,481 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::State;
+use crate::api::{GithubSkin, HttpClient};
+use crate::launcher::guard::{self, GuardedError};
+use crate::launcher::GameProcesses;
+use crate::commands::network::fetch_skins_from_sources;
+use crate::models::{SkinInstallRecord, SKIN_INSTALL_FILE};
+use crate::settings::SettingsStore;
+use crate::utils::{json_edit, sanitize_file_name};
+
+/// MajdataPlay 设置文件名（位于游戏目录）
+const GAME_SETTINGS_FILE: &str = "settings.json";
+
+/// 设置文件中当前皮肤字段的路径
+const ACTIVE_SKIN_PATH: [&str; 2] = ["Display", "Skin"];
+
+/// 游戏设置文件路径
+fn settings_path(game_dir: &Path) -> PathBuf {
+    game_dir.join(GAME_SETTINGS_FILE)
+}
+
+/// 读取游戏设置文件文本
+fn read_settings(game_dir: &Path) -> Result<String, String> {
+    let path = settings_path(game_dir);
+    if !path.exists() {
+        return Err(format!("游戏设置文件不存在，请先启动一次游戏: {}", path.display()));
+    }
+    std::fs::read_to_string(&path)
+        .map_err(|e| {
+            tracing::error!("读取游戏设置失败: {}", e);
+            format!("读取游戏设置失败: {}", e)
+        })
+}
+
+/// 皮肤文件夹路径
+///
+/// 皮肤名来自前端，必须是单个安全的文件夹名（不能为空、包含路径分隔符或 `..`）。
+pub fn skin_dir(skins_dir: &Path, skin_name: &str) -> Result<PathBuf, String> {
+    if sanitize_file_name(skin_name).as_deref() != Some(skin_name) {
+        tracing::error!("无效的皮肤名称: {:?}", skin_name);
+        return Err(format!("无效的皮肤名称: {}", skin_name));
+    }
+    Ok(skins_dir.join(skin_name))
+}
+
+/// Tauri命令：获取 MajdataPlay 当前使用的皮肤
+#[tauri::command]
+pub fn get_active_skin(settings: State<'_, SettingsStore>) -> Result<String, String> {
+    let text = read_settings(&settings.get().game_dir())?;
+    let span = json_edit::find_value_span(&text, &ACTIVE_SKIN_PATH)
+        .ok_or_else(|| format!("游戏设置中未找到字段: {}", ACTIVE_SKIN_PATH.join(".")))?;
+    serde_json::from_str::<String>(&text[span])
+        .map_err(|e| format!("皮肤字段格式无效: {}", e))
+}
+
+/// Tauri命令：设置 MajdataPlay 使用的皮肤
+///
+/// 只修改设置文件中的皮肤字段，原有格式和未知字段保持不变。皮肤必须存在于 Skins 目录。
+#[tauri::command]
+pub fn set_active_skin(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    skin_name: String
+) -> Result<(), GuardedError> {
+    tracing::info!("切换游戏皮肤: {}", skin_name);
+    let settings = settings.get();
+    let game_dir = settings.game_dir();
+    // 游戏退出时会写回设置文件，运行中修改会被覆盖
+    guard::ensure_not_running(&games, &game_dir)?;
+
+    let skin_path = skin_dir(&settings.skins_dir(), &skin_name)?;
+    if !skin_path.is_dir() {
+        tracing::error!("皮肤不存在: {:?}", skin_path);
+        return Err(format!("皮肤不存在: {}", skin_path.display()).into());
+    }
+
+    let text = read_settings(&game_dir)?;
+    let new_value = serde_json::to_string(&skin_name)
+        .map_err(|e| format!("Failed to serialize skin name: {}", e))?;
+    let updated = json_edit::replace_value(&text, &ACTIVE_SKIN_PATH, &new_value)?;
+
+    // 写入前确认结果仍是合法 JSON
+    let check = updated.trim_start_matches('\u{feff}');
+    serde_json::from_str::<serde_json::Value>(check)
+        .map_err(|e| format!("修改后的设置文件无效: {}", e))?;
+
+    let path = settings_path(&game_dir);
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, updated)
+        .and_then(|_| std::fs::rename(&temp_path, &path))
+        .map_err(|e| {
+            tracing::error!("写入游戏设置失败: {}", e);
+            format!("写入游戏设置失败: {}", e)
+        })?;
+
+    tracing::info!("游戏皮肤已切换为: {}", skin_name);
+    Ok(())
+}
+
+/// 可更新的皮肤
+#[derive(Debug, Clone, Serialize)]
+pub struct SkinUpdate {
+    /// 本地皮肤文件夹名
+    pub name: String,
+    pub installed: SkinInstallRecord,
+    pub latest: GithubSkin,
+}
+
+/// 读取皮肤文件夹中的安装记录
+fn read_install_record(skin_dir: &Path) -> Option<SkinInstallRecord> {
+    let text = std::fs::read_to_string(skin_dir.join(SKIN_INSTALL_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// 上游文件是否与安装时不同：有 SHA 时比较 SHA，否则比较大小
+fn is_outdated(installed: &SkinInstallRecord, latest: &GithubSkin) -> bool {
+    match (&installed.sha, &latest.sha) {
+        (Some(a), Some(b)) => a != b,
+        _ => installed.size != latest.size,
+    }
+}
+
+/// Tauri命令：检查已安装皮肤是否有更新
+///
+/// 只检查带安装记录的皮肤（通过 `download_skin_zip` 安装），按来源和 zip 文件名匹配设置中皮肤来源的在线列表。
+/// 返回有更新的皮肤，升级时以 `latest` 调用 `download_skin_zip` 即可原地替换并备份旧版本。
+#[tauri::command]
+pub async fn check_skin_updates(
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>
+) -> Result<Vec<SkinUpdate>, String> {
+    let settings = settings.get();
+    let skins_dir = settings.skins_dir();
+    tracing::info!("检查皮肤更新: {:?}", skins_dir);
+
+    let mut installed = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(&skins_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            if let Some(record) = read_install_record(&path) {
+                installed.push((entry.file_name().to_string_lossy().to_string(), record));
+            }
+        }
+    }
+    if installed.is_empty() {
+        tracing::info!("没有带安装记录的皮肤");
+        return Ok(Vec::new());
+    }
+
+    let online = fetch_skins_from_sources(settings.skin_sources(), &http.get(&settings)?).await?;
+
+    let updates: Vec<SkinUpdate> = installed
+        .into_iter()
+        .filter_map(|(name, record)| {
+            let latest = online.iter().find(|skin| {
+                skin.name == record.file_name
+                    && (record.source.is_empty() || skin.source == record.source)
+            })?;
+            is_outdated(&record, latest).then(|| SkinUpdate {
+                name,
+                installed: record,
+                latest: latest.clone(),
+            })
+        })
+        .collect();
+
+    tracing::info!("检查皮肤更新完成: {} 个可更新", updates.len());
+    Ok(updates)
+}
+
+/// MajdataPlay 加载皮肤时必需的精灵图（文件名不含扩展名，不区分大小写）
+const REQUIRED_SPRITES: [&str; 18] = [
+    "Outline",
+    "Tap", "Tap_Each", "Tap_Break", "Tap_Ex",
+    "Hold", "Hold_Each", "Hold_Break", "Hold_Ex",
+    "Star", "Star_Each", "Star_Break", "Star_Double",
+    "Slide", "Slide_Each", "Slide_Break",
+    "Touch", "Touch_Each",
+];
+
+/// 游戏支持的图片扩展名
+const SKIN_IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// 皮肤中允许出现的非图片文件扩展名（清单、说明和音效）
+const SKIN_EXTRA_EXTENSIONS: [&str; 7] = ["json", "txt", "md", "wav", "mp3", "ogg", "ttf"];
+
+/// 精灵图最大边长（像素）
+const MAX_SPRITE_SIZE: u32 = 4096;
+
+/// 有问题的皮肤文件
+#[derive(Debug, Clone, Serialize)]
+pub struct SkinFileIssue {
+    /// 相对于皮肤文件夹的路径
+    pub file: String,
+    pub reason: String,
+}
+
+/// 皮肤校验结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SkinValidation {
+    pub valid: bool,
+    /// 缺失的必需精灵图
+    pub missing: Vec<String>,
+    /// 游戏不会使用的文件
+    pub unexpected: Vec<String>,
+    /// 格式或尺寸不正确的图片
+    pub invalid: Vec<SkinFileIssue>,
+    /// 布局提示，例如精灵图被多包了一层文件夹
+    pub hint: Option<String>,
+}
+
+/// 检查单张图片：实际格式与扩展名一致，尺寸在合理范围内
+fn check_image(path: &Path, ext: &str) -> Result<(), String> {
+    let reader = image::ImageReader::open(path)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| format!("无法读取: {}", e))?;
+    let expected = if ext == "png" { image::ImageFormat::Png } else { image::ImageFormat::Jpeg };
+    match reader.format() {
+        Some(format) if format == expected => {}
+        Some(format) => return Err(format!("实际格式为 {:?}，与扩展名 .{} 不符", format, ext)),
+        None => return Err("无法识别的图片格式".to_string()),
+    }
+    let (width, height) = reader.into_dimensions().map_err(|e| format!("无法解析尺寸: {}", e))?;
+    if width == 0 || height == 0 || width > MAX_SPRITE_SIZE || height > MAX_SPRITE_SIZE {
+        return Err(format!("尺寸 {}x{} 超出范围（1-{}）", width, height, MAX_SPRITE_SIZE));
+    }
+    Ok(())
+}
+
+/// 校验皮肤文件夹
+pub fn validate_skin_dir(skin_dir: &Path) -> Result<SkinValidation, String> {
+    if !skin_dir.is_dir() {
+        return Err(format!("皮肤不存在: {}", skin_dir.display()));
+    }
+
+    let mut root_sprites = Vec::new();
+    let mut unexpected = Vec::new();
+    let mut invalid = Vec::new();
+
+    for entry in walkdir::WalkDir::new(skin_dir).into_iter().flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let relative = path.strip_prefix(skin_dir).unwrap_or(path);
+        let relative_name = relative.to_string_lossy().replace('\\', "/");
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if SKIN_IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+            if let Err(reason) = check_image(path, &ext) {
+                invalid.push(SkinFileIssue { file: relative_name.clone(), reason });
+            }
+            if relative.parent().is_some_and(|p| p.as_os_str().is_empty()) {
+                if let Some(stem) = path.file_stem() {
+                    root_sprites.push(stem.to_string_lossy().to_lowercase());
+                }
+            }
+        } else if !SKIN_EXTRA_EXTENSIONS.contains(&ext.as_str()) {
+            unexpected.push(relative_name);
+        }
+    }
+
+    let missing: Vec<String> = REQUIRED_SPRITES
+        .iter()
+        .filter(|name| !root_sprites.contains(&name.to_lowercase()))
+        .map(|name| format!("{}.png", name))
+        .collect();
+
+    // 根目录没有任何必需精灵图，但唯一的子文件夹里有：zip 多包了一层
+    let mut hint = None;
+    if missing.len() == REQUIRED_SPRITES.len() {
+        let subdirs: Vec<PathBuf> = std::fs::read_dir(skin_dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+            .unwrap_or_default();
+        if let [subdir] = subdirs.as_slice() {
+            if REQUIRED_SPRITES.iter().any(|name| subdir.join(format!("{}.png", name)).exists()) {
+                hint = Some(format!(
+                    "精灵图位于子文件夹 {} 中，请将其内容移动到皮肤根目录",
+                    subdir.file_name().unwrap_or_default().to_string_lossy()
+                ));
+            }
+        }
+    }
+
+    unexpected.sort();
+    Ok(SkinValidation {
+        valid: missing.is_empty() && invalid.is_empty(),
+        missing,
+        unexpected,
+        invalid,
+        hint,
+    })
+}
+
+/// Tauri命令：校验已安装的皮肤
+///
+/// 检查必需的精灵图是否齐全、图片格式和尺寸是否正确，返回缺失和多余的文件。
+#[tauri::command]
+pub async fn validate_skin(settings: State<'_, SettingsStore>, skin_name: String) -> Result<SkinValidation, String> {
+    let skin_dir = skin_dir(&settings.get().skins_dir(), &skin_name)?;
+    let result = tokio::task::spawn_blocking(move || validate_skin_dir(&skin_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    log_validation(&skin_name, &result);
+    Ok(result)
+}
+
+/// 记录校验结果
+pub fn log_validation(skin_name: &str, result: &SkinValidation) {
+    if result.valid {
+        tracing::info!("皮肤校验通过: {} ({} 个多余文件)", skin_name, result.unexpected.len());
+    } else {
+        tracing::warn!(
+            "皮肤校验未通过: {} (缺失 {} 个, 无效 {} 个)",
+            skin_name,
+            result.missing.len(),
+            result.invalid.len()
+        );
+    }
+}
+
+/// 组合皮肤的来源记录文件名（位于生成的皮肤文件夹根目录）
+const COMPOSE_RECORD_FILE: &str = "compose.json";
+
+/// 组合皮肤的来源记录，同时写入 compose.json
+#[derive(Debug, Clone, Serialize)]
+pub struct ComposedSkin {
+    pub name: String,
+    pub base: String,
+    pub overlays: Vec<String>,
+    /// 相对路径 → 来源皮肤名
+    pub files: BTreeMap<String, String>,
+}
+
+/// 组合结果：来源记录和对新皮肤的校验
+#[derive(Debug, Clone, Serialize)]
+pub struct ComposeResult {
+    #[serde(flatten)]
+    pub skin: ComposedSkin,
+    pub validation: SkinValidation,
+}
+
+/// 文件在各层之间的匹配键：不区分大小写，图片忽略扩展名（上层 Tap.jpg 会替换下层 Tap.png）
+fn layer_key(relative: &str) -> String {
+    let lower = relative.to_lowercase();
+    match lower.rsplit_once('.') {
+        Some((stem, ext)) if SKIN_IMAGE_EXTENSIONS.contains(&ext) => stem.to_string(),
+        _ => lower,
+    }
+}
+
+/// 逐层收集文件并复制到输出文件夹，返回相对路径 → 来源皮肤名
+fn compose_layers(skins_dir: &Path, layers: &[String], output_dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    // 匹配键 → (相对路径, 来源皮肤名, 源文件)
+    let mut chosen: HashMap<String, (String, String, PathBuf)> = HashMap::new();
+    for layer in layers {
+        let layer_dir = skins_dir.join(layer);
+        for entry in walkdir::WalkDir::new(&layer_dir).into_iter().flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let relative = path
+                .strip_prefix(&layer_dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            // 安装记录和组合记录属于原皮肤，不参与叠加
+            if relative.eq_ignore_ascii_case(SKIN_INSTALL_FILE) || relative.eq_ignore_ascii_case(COMPOSE_RECORD_FILE) {
+                continue;
+            }
+            chosen.insert(layer_key(&relative), (relative, layer.clone(), path.to_path_buf()));
+        }
+    }
+
+    // 先写入临时文件夹，完成后再重命名
+    let staging_dir = output_dir.with_file_name(format!(
+        ".{}.partial",
+        output_dir.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    if staging_dir.exists() {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+    }
+
+    let copy_all = || -> Result<BTreeMap<String, String>, String> {
+        let mut files = BTreeMap::new();
+        for (relative, layer, source) in chosen.values() {
+            let target = staging_dir.join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+            }
+            std::fs::copy(source, &target)
+                .map_err(|e| format!("复制文件失败 {}: {}", relative, e))?;
+            files.insert(relative.clone(), layer.clone());
+        }
+        Ok(files)
+    };
+    let files = match copy_all() {
+        Ok(files) => files,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+    };
+
+    std::fs::rename(&staging_dir, output_dir)
+        .map_err(|e| format!("Failed to create skin folder: {}", e))?;
+    Ok(files)
+}
+
+/// Tauri命令：将多个皮肤叠加为新皮肤
+///
+/// 以 `base` 为底，按顺序叠加 `overlays`（后面的覆盖前面的），结果写入 `Skins/<output_name>`，
+/// 并在 compose.json 中记录每个文件来自哪一层。游戏运行时拒绝写入。
+#[tauri::command]
+pub async fn compose_skin(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    base: String,
+    overlays: Vec<String>,
+    output_name: String
+) -> Result<ComposeResult, GuardedError> {
+    tracing::info!("组合皮肤: {} + {:?} -> {}", base, overlays, output_name);
+    let start_time = std::time::Instant::now();
+
+    let output_name = sanitize_file_name(&output_name)
+        .ok_or_else(|| format!("无效的皮肤名称: {}", output_name))?;
+    let skins_path = settings.get().skins_dir();
+    let output_dir = skins_path.join(&output_name);
+    guard::ensure_not_running(&games, &output_dir)?;
+    if output_dir.exists() {
+        return Err(format!("皮肤已存在: {}", output_dir.display()).into());
+    }
+
+    let layers: Vec<String> = std::iter::once(base.clone()).chain(overlays.iter().cloned()).collect();
+    for layer in &layers {
+        let layer_dir = skin_dir(&skins_path, layer)?;
+        if !layer_dir.is_dir() {
+            return Err(format!("皮肤不存在: {}", layer_dir.display()).into());
+        }
+    }
+
+    let record_path = output_dir.join(COMPOSE_RECORD_FILE);
+    let (files, validation) = tokio::task::spawn_blocking(move || {
+        let files = compose_layers(&skins_path, &layers, &output_dir)?;
+        let validation = validate_skin_dir(&output_dir)?;
+        Ok::<_, String>((files, validation))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| {
+        tracing::error!("组合皮肤失败: {}", e);
+        e
+    })?;
+
+    let composed = ComposedSkin {
+        name: output_name.clone(),
+        base,
+        overlays,
+        files,
+    };
+    let record_json = serde_json::to_string_pretty(&composed)
+        .map_err(|e| format!("Failed to serialize compose record: {}", e))?;
+    if let Err(e) = tokio::fs::write(&record_path, record_json).await {
+        tracing::warn!("写入皮肤组合记录失败: {}", e);
+    }
+
+    log_validation(&output_name, &validation);
+    let elapsed = start_time.elapsed();
+    tracing::info!("皮肤组合完成: {} ({} 个文件, {:.2}s)", output_name, composed.files.len(), elapsed.as_secs_f64());
+    Ok(ComposeResult { skin: composed, validation })
+}
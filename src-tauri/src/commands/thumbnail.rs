@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use futures_util::stream::{self, StreamExt};
+use image::codecs::jpeg::JpegEncoder;
+use tauri::{Manager, State};
+use crate::api::{HttpClient, MajdataClient};
+use crate::settings::SettingsStore;
+use crate::utils::{disk_cache, sanitize_file_name};
+
+/// 缩略图目录名（位于应用缓存目录）
+const THUMBNAIL_DIR_NAME: &str = "thumbnails";
+
+/// 缩略图最大边长（像素）
+const THUMBNAIL_MAX_SIZE: u32 = 256;
+
+/// 缩略图 JPEG 质量
+const THUMBNAIL_QUALITY: u8 = 85;
+
+/// 缩略图目录最多占用的字节数，超出后按最近使用时间淘汰
+const THUMBNAIL_CACHE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 同时下载的缩略图数量
+const THUMBNAIL_CONCURRENCY: usize = 4;
+
+/// 获取缩略图缓存目录
+fn thumbnail_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join(THUMBNAIL_DIR_NAME))
+        .map_err(|e| format!("Failed to get app cache directory: {}", e))
+}
+
+/// 缩略图文件路径
+fn thumbnail_path(dir: &Path, chart_id: &str) -> Option<PathBuf> {
+    sanitize_file_name(chart_id).map(|name| dir.join(format!("{}.jpg", name)))
+}
+
+/// 缩放图片并编码为 JPEG（CPU 密集，在阻塞线程中执行）
+fn make_thumbnail(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let img = image::load_from_memory(bytes)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let thumb = img.thumbnail(THUMBNAIL_MAX_SIZE, THUMBNAIL_MAX_SIZE).to_rgb8();
+
+    let mut output = Vec::new();
+    JpegEncoder::new_with_quality(&mut output, THUMBNAIL_QUALITY)
+        .encode_image(&thumb)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(output)
+}
+
+/// 获取单个谱面的缩略图，已缓存时只更新访问时间
+async fn fetch_thumbnail(client: &MajdataClient, dir: &Path, chart_id: &str) -> Result<PathBuf, String> {
+    let path = thumbnail_path(dir, chart_id)
+        .ok_or_else(|| format!("无效的谱面 ID: {}", chart_id))?;
+
+    if path.exists() {
+        disk_cache::touch(&path);
+        return Ok(path);
+    }
+
+    let (bytes, _) = client.get_bytes(&client.image_url(chart_id, false)).await?;
+    let thumb = tokio::task::spawn_blocking(move || make_thumbnail(&bytes))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    // 先写临时文件再重命名，避免并发请求读到不完整的文件
+    let temp_path = path.with_extension("jpg.part");
+    tokio::fs::write(&temp_path, thumb)
+        .await
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+    tokio::fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+    Ok(path)
+}
+
+/// Tauri命令：预取一页谱面的封面缩略图
+///
+/// 返回谱面 ID → 本地缩略图路径，前端通过 asset 协议（`convertFileSrc`）加载。
+/// 下载失败的谱面不会出现在结果中。
+#[tauri::command]
+pub async fn prefetch_chart_thumbnails(
+    app: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    chart_ids: Vec<String>
+) -> Result<HashMap<String, String>, String> {
+    tracing::info!("预取谱面缩略图: {} 个", chart_ids.len());
+    let start_time = std::time::Instant::now();
+
+    let dir = thumbnail_dir(&app)?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| {
+            tracing::error!("创建缩略图目录失败: {}", e);
+            format!("Failed to create thumbnail directory: {}", e)
+        })?;
+
+    let settings = settings.get();
+    let client = MajdataClient::new(settings.api_base(), http.get(&settings)?)?;
+
+    let results: Vec<(String, Result<PathBuf, String>)> = stream::iter(chart_ids)
+        .map(|chart_id| {
+            let client = &client;
+            let dir = &dir;
+            async move {
+                let result = fetch_thumbnail(client, dir, &chart_id).await;
+                (chart_id, result)
+            }
+        })
+        .buffer_unordered(THUMBNAIL_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut thumbnails = HashMap::new();
+    for (chart_id, result) in results {
+        match result {
+            Ok(path) => {
+                thumbnails.insert(chart_id, path.to_string_lossy().to_string());
+            }
+            Err(e) => tracing::warn!("  ✗ 缩略图 {} 获取失败: {}", chart_id, e),
+        }
+    }
+
+    let evict_dir = dir.clone();
+    tokio::task::spawn_blocking(move || disk_cache::evict_lru_files(&evict_dir, THUMBNAIL_CACHE_MAX_BYTES));
+
+    let elapsed = start_time.elapsed();
+    tracing::info!("缩略图预取完成: {} 个, {:.2}s", thumbnails.len(), elapsed.as_secs_f64());
+
+    Ok(thumbnails)
+}
+
+/// Tauri命令：清除缩略图缓存
+#[tauri::command]
+pub async fn clear_thumbnail_cache(app: tauri::AppHandle) -> Result<(), String> {
+    let dir = thumbnail_dir(&app)?;
+    if !dir.exists() {
+        return Ok(());
+    }
+    tokio::fs::remove_dir_all(&dir)
+        .await
+        .map_err(|e| {
+            tracing::error!("清除缩略图缓存失败: {}", e);
+            format!("Failed to clear thumbnail cache: {}", e)
+        })?;
+    tracing::info!("已清除缩略图缓存: {:?}", dir);
+    Ok(())
+}
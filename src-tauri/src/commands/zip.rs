@@ -1,10 +1,29 @@
 use std::path::Path;
 use tokio::fs;
+use tauri::State;
+use crate::api::HttpClient;
 use crate::commands::network::download_file_impl;
+use crate::launcher::guard::{self, GuardedError};
+use crate::launcher::GameProcesses;
+use crate::settings::SettingsStore;
 
 /// Tauri命令：解压ZIP文件
+///
+/// 游戏正在从目标目录运行时拒绝解压。
 #[tauri::command]
-pub fn extract_zip(zip_path: String, target_dir: String) -> Result<String, String> {
+pub fn extract_zip(
+    games: State<'_, GameProcesses>,
+    zip_path: String,
+    target_dir: String
+) -> Result<String, GuardedError> {
+    guard::ensure_not_running(&games, Path::new(&target_dir))?;
+    Ok(extract_zip_impl(zip_path, target_dir)?)
+}
+
+/// 解压 ZIP 文件到目标目录（去掉压缩包的根文件夹）
+///
+/// 不检查游戏是否在运行，调用方需自行调用 [`guard::ensure_not_running`]。
+pub(crate) fn extract_zip_impl(zip_path: String, target_dir: String) -> Result<String, String> {
     tracing::info!("开始解压 ZIP 文件: {} -> {}", zip_path, target_dir);
     let start_time = std::time::Instant::now();
     
@@ -75,9 +94,19 @@ pub fn extract_zip(zip_path: String, target_dir: String) -> Result<String, Strin
 }
 
 /// Tauri命令：下载并解压文件
+///
+/// 游戏正在从目标目录运行时拒绝更新。
 #[tauri::command]
-pub async fn download_and_extract(url: String, target_path: String, zip_path: String, proxy: Option<String>) -> Result<String, String> {
+pub async fn download_and_extract(
+    games: State<'_, GameProcesses>,
+    settings: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    url: String,
+    target_path: String,
+    zip_path: String
+) -> Result<String, GuardedError> {
     tracing::info!("开始下载并解压: {} -> {}", url, target_path);
+    guard::ensure_not_running(&games, Path::new(&target_path))?;
     let start_time = std::time::Instant::now();
     
     tracing::debug!("创建目标目录: {}", target_path);
@@ -89,11 +118,12 @@ pub async fn download_and_extract(url: String, target_path: String, zip_path: St
         })?;
     
     // 下载文件
-    download_file_impl(url, zip_path.clone(), proxy).await?;
+    let client = http.get(&settings.get())?;
+    download_file_impl(&client, url, zip_path.clone()).await?;
     
     // 解压文件
     tracing::info!("开始解压 ZIP 文件...");
-    let extract_result = extract_zip(zip_path.clone(), target_path)?;
+    let extract_result = extract_zip_impl(zip_path.clone(), target_path)?;
     
     // 删除 ZIP 文件
     tracing::debug!("删除临时 ZIP 文件: {}", zip_path);
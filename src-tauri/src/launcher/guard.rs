@@ -0,0 +1,157 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use super::process::{normalize, GameProcesses};
+
+/// 游戏主程序文件名
+const GAME_EXE_NAME: &str = "MajdataPlay.exe";
+
+/// 游戏正在运行，拒绝修改其目录中的文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename = "game_running")]
+pub struct GameRunning {
+    /// 进程 ID；仅通过文件占用检测到时为空
+    pub pid: Option<u32>,
+    pub game_dir: String,
+    pub message: String,
+}
+
+impl GameRunning {
+    fn new(pid: Option<u32>, game_dir: &Path) -> Self {
+        let message = match pid {
+            Some(pid) => format!("MajdataPlay 正在运行 (PID {})，请先关闭游戏再修改游戏文件", pid),
+            None => "MajdataPlay 正在运行，请先关闭游戏再修改游戏文件".to_string(),
+        };
+        GameRunning { pid, game_dir: game_dir.to_string_lossy().to_string(), message }
+    }
+}
+
+impl fmt::Display for GameRunning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for GameRunning {}
+
+/// 会修改游戏文件的命令的错误类型
+///
+/// 游戏运行时序列化为 `{ kind: "game_running", pid, game_dir, message }`，其他错误仍为字符串。
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum GuardedError {
+    GameRunning(GameRunning),
+    Other(String),
+}
+
+impl fmt::Display for GuardedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuardedError::GameRunning(e) => e.fmt(f),
+            GuardedError::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for GuardedError {}
+
+impl From<GameRunning> for GuardedError {
+    fn from(e: GameRunning) -> Self {
+        GuardedError::GameRunning(e)
+    }
+}
+
+impl From<String> for GuardedError {
+    fn from(msg: String) -> Self {
+        GuardedError::Other(msg)
+    }
+}
+
+/// 查找 `path` 所属的游戏目录（自身或最近的包含 MajdataPlay.exe 的上级目录）
+fn find_game_dir(path: &Path) -> Option<PathBuf> {
+    normalize(path)
+        .ancestors()
+        .find(|dir| dir.join(GAME_EXE_NAME).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// 游戏运行时拒绝修改 `path`（游戏目录或其中的文件）
+///
+/// 先检查由本工具启动的进程，再检测从该目录启动的外部进程。
+pub fn ensure_not_running(games: &GameProcesses, path: &Path) -> Result<(), GameRunning> {
+    if let Some(game) = games.running_in(path) {
+        tracing::warn!("游戏正在运行 (PID {})，拒绝修改: {:?}", game.pid, path);
+        return Err(GameRunning::new(Some(game.pid), Path::new(&game.game_dir)));
+    }
+
+    let Some(game_dir) = find_game_dir(path) else {
+        return Ok(());
+    };
+    match detect_external(&game_dir) {
+        Some(pid) => {
+            tracing::warn!("检测到外部启动的游戏 (PID {:?})，拒绝修改: {:?}", pid, path);
+            Err(GameRunning::new(pid, &game_dir))
+        }
+        None => Ok(()),
+    }
+}
+
+/// 检测从游戏目录启动的外部进程：Windows 上运行中的 exe 无法以写方式打开
+#[cfg(target_os = "windows")]
+fn detect_external(game_dir: &Path) -> Option<Option<u32>> {
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    match std::fs::OpenOptions::new().write(true).open(game_dir.join(GAME_EXE_NAME)) {
+        Err(e) if e.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => Some(None),
+        _ => None,
+    }
+}
+
+/// 检测从游戏目录启动的外部进程：扫描 /proc 中命令行包含该 MajdataPlay.exe 的进程（含 Wine/Proton）
+#[cfg(target_os = "linux")]
+fn detect_external(game_dir: &Path) -> Option<Option<u32>> {
+    let target = normalize(&game_dir.join(GAME_EXE_NAME));
+    let entries = std::fs::read_dir("/proc").ok()?;
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(cmdline) = std::fs::read(entry.path().join("cmdline")) else {
+            continue;
+        };
+        let cwd = std::fs::read_link(entry.path().join("cwd")).ok();
+        let matched = cmdline
+            .split(|b| *b == 0)
+            .filter_map(|arg| std::str::from_utf8(arg).ok())
+            .filter_map(|arg| wine_arg_to_path(arg, cwd.as_deref()))
+            .any(|path| normalize(&path) == target);
+        if matched {
+            return Some(Some(pid));
+        }
+    }
+    None
+}
+
+/// 其他平台无法检测外部进程
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn detect_external(_game_dir: &Path) -> Option<Option<u32>> {
+    None
+}
+
+/// 将命令行参数中的 MajdataPlay.exe 路径转换为本地路径（支持 Wine 的 Z: 盘路径和相对路径）
+#[cfg(target_os = "linux")]
+fn wine_arg_to_path(arg: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    let file_name = arg.rsplit(['/', '\\']).next()?;
+    if !file_name.eq_ignore_ascii_case(GAME_EXE_NAME) {
+        return None;
+    }
+    let unix = match arg.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("z:\\") => format!("/{}", arg[3..].replace('\\', "/")),
+        _ => arg.replace('\\', "/"),
+    };
+    let path = PathBuf::from(unix);
+    if path.is_absolute() {
+        Some(path)
+    } else {
+        cwd.map(|cwd| cwd.join(path))
+    }
+}
@@ -0,0 +1,8 @@
+pub mod guard;
+pub mod process;
+pub mod profile;
+pub mod runner;
+
+pub use process::GameProcesses;
+pub use profile::LaunchProfile;
+pub use runner::LaunchRunner;
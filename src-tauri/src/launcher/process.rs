@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+
+/// 游戏启动事件名
+pub const GAME_STARTED_EVENT: &str = "game-started";
+
+/// 游戏退出事件名
+pub const GAME_EXITED_EVENT: &str = "game-exited";
+
+/// 由本工具启动、仍在运行的游戏进程
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningGame {
+    pub pid: u32,
+    pub profile_id: String,
+    pub game_dir: String,
+    pub started_at: String,
+    /// 游戏标准输出和标准错误的记录文件
+    pub log_path: Option<String>,
+}
+
+/// 游戏退出事件
+#[derive(Debug, Clone, Serialize)]
+pub struct GameExit {
+    #[serde(flatten)]
+    pub game: RunningGame,
+    /// 退出码；被信号终止时为空
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_secs: f64,
+}
+
+/// 已启动的游戏进程（Tauri 托管状态）
+#[derive(Default)]
+pub struct GameProcesses {
+    games: Mutex<HashMap<u32, RunningGame>>,
+}
+
+impl GameProcesses {
+    pub fn insert(&self, game: RunningGame) {
+        if let Ok(mut games) = self.games.lock() {
+            games.insert(game.pid, game);
+        }
+    }
+
+    pub fn remove(&self, pid: u32) -> Option<RunningGame> {
+        self.games.lock().ok()?.remove(&pid)
+    }
+
+    pub fn list(&self) -> Vec<RunningGame> {
+        self.games
+            .lock()
+            .map(|games| games.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 查找从 `path` 所在游戏目录启动的进程（`path` 可以是游戏目录或其中的文件）
+    pub fn running_in(&self, path: &Path) -> Option<RunningGame> {
+        let path = normalize(path);
+        self.list()
+            .into_iter()
+            .find(|game| path.starts_with(normalize(Path::new(&game.game_dir))))
+    }
+}
+
+/// 规范化路径用于比较
+///
+/// 路径不存在时规范化最近的已存在上级目录再拼回剩余部分；Windows 上去掉 `\\?\` 前缀。
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    let canonical = loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            break canonical;
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    };
+    let text = canonical.to_string_lossy();
+    let mut normalized = match text.strip_prefix(r"\\?\") {
+        Some(stripped) => PathBuf::from(stripped),
+        None => canonical.clone(),
+    };
+    normalized.extend(rest.iter().rev());
+    normalized
+}
+
+/// 将子进程的一个输出流逐行写入日志文件
+///
+/// 一直读到流结束：非 UTF-8 内容（如 Wine 输出的本地编码）按有损方式转换，写入失败也继续读取，
+/// 保证管道不会因为无人读取而写满。
+pub fn capture_output<R>(stream: R, label: &'static str, file: Arc<Mutex<File>>)
+where
+    R: Read + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_end_matches(['\r', '\n']);
+            tracing::debug!("[game {}] {}", label, line);
+            if let Ok(mut file) = file.lock() {
+                let _ = writeln!(file, "[{}] {}", label, line);
+            }
+        }
+    });
+}
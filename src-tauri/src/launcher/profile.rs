@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// 启动配置文件名（位于应用配置目录）
+pub const LAUNCH_PROFILES_FILE: &str = "launch_profiles.json";
+
+/// 串行化配置文件的读写
+static PROFILES_LOCK: Mutex<()> = Mutex::new(());
+
+/// 启动配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchProfile {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: String,
+    /// 传给 MajdataPlay.exe 的参数
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 额外的环境变量，覆盖运行器中的同名变量
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// 工作目录，相对路径基于游戏目录；为空时使用游戏目录
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// 启动前执行的命令，失败时取消启动
+    #[serde(default)]
+    pub pre_launch: Option<String>,
+    /// 游戏退出后执行的命令
+    #[serde(default)]
+    pub post_exit: Option<String>,
+}
+
+impl LaunchProfile {
+    fn builtin(id: &str, label: &str, description: &str, args: &[&str]) -> Self {
+        LaunchProfile {
+            id: id.to_string(),
+            label: label.to_string(),
+            description: description.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: BTreeMap::new(),
+            working_dir: None,
+            pre_launch: None,
+            post_exit: None,
+        }
+    }
+
+    /// 内置启动配置，首次使用时写入配置文件
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::builtin("default", "默认启动", "使用默认设置启动游戏", &[]),
+            Self::builtin("d3d11", "强制使用 D3D11", "强制使用 Direct3D 11 渲染", &["-force-d3d11"]),
+            Self::builtin("d3d12", "强制使用 D3D12", "强制使用 Direct3D 12 渲染", &["-force-d3d12"]),
+            Self::builtin("opengl", "强制使用 OpenGL", "强制使用 OpenGL Core 渲染", &["-force-glcore"]),
+            Self::builtin("vulkan", "强制使用 Vulkan", "强制使用 Vulkan 渲染", &["-force-vulkan"]),
+            Self::builtin("test", "测试模式", "进入游戏测试模式", &["--test-mode"]),
+            Self::builtin("edit", "MajdataEdit", "打开谱面编辑器", &["--view-mode"]),
+        ]
+    }
+
+    /// 工作目录
+    pub fn resolve_working_dir(&self, game_dir: &Path) -> PathBuf {
+        match self.working_dir.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            Some(dir) => game_dir.join(dir),
+            None => game_dir.to_path_buf(),
+        }
+    }
+}
+
+/// 读取启动配置；文件不存在时写入内置配置
+pub fn load_profiles(config_dir: &Path) -> Result<Vec<LaunchProfile>, String> {
+    let _guard = PROFILES_LOCK.lock().map_err(|e| format!("锁定启动配置失败: {}", e))?;
+    read_profiles(config_dir)
+}
+
+/// 修改启动配置并写回
+pub fn update_profiles<F>(config_dir: &Path, f: F) -> Result<Vec<LaunchProfile>, String>
+where
+    F: FnOnce(&mut Vec<LaunchProfile>) -> Result<(), String>,
+{
+    let _guard = PROFILES_LOCK.lock().map_err(|e| format!("锁定启动配置失败: {}", e))?;
+    let mut profiles = read_profiles(config_dir)?;
+    f(&mut profiles)?;
+    write_profiles(config_dir, &profiles)?;
+    Ok(profiles)
+}
+
+fn read_profiles(config_dir: &Path) -> Result<Vec<LaunchProfile>, String> {
+    let path = config_dir.join(LAUNCH_PROFILES_FILE);
+    if !path.exists() {
+        let profiles = LaunchProfile::defaults();
+        write_profiles(config_dir, &profiles)?;
+        tracing::info!("已写入内置启动配置: {:?}", path);
+        return Ok(profiles);
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("读取启动配置失败: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| {
+        tracing::error!("解析启动配置失败: {}", e);
+        format!("启动配置格式无效 ({}): {}", path.display(), e)
+    })
+}
+
+fn write_profiles(config_dir: &Path, profiles: &[LaunchProfile]) -> Result<(), String> {
+    std::fs::create_dir_all(config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    let json = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("Failed to serialize launch profiles: {}", e))?;
+    let path = config_dir.join(LAUNCH_PROFILES_FILE);
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, json)
+        .and_then(|_| std::fs::rename(&temp_path, &path))
+        .map_err(|e| format!("写入启动配置失败: {}", e))
+}
+
+/// 构建钩子命令：按命令行拆分后直接执行，不经过 shell
+pub fn hook_command(hook: &str, profile: &LaunchProfile, working_dir: &Path) -> Result<Command, String> {
+    let parts = super::runner::split_command_line(hook)?;
+    let (program, args) = parts
+        .split_first()
+        .ok_or_else(|| "钩子命令为空".to_string())?;
+    let mut command = Command::new(program);
+    command.args(args).current_dir(working_dir).envs(&profile.env);
+    Ok(command)
+}
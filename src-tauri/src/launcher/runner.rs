@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde::{Deserialize, Serialize};
+
+/// 运行器类型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunnerKind {
+    /// 直接运行（Windows）
+    Native,
+    /// 通过 Wine 运行
+    Wine {
+        /// wine 可执行文件，默认从 PATH 查找 `wine`
+        #[serde(default)]
+        wine: Option<String>,
+        /// WINEPREFIX
+        #[serde(default)]
+        prefix: Option<String>,
+    },
+    /// 通过 Proton 运行（`proton run <exe>`）
+    Proton {
+        /// proton 脚本路径
+        proton: String,
+        /// 兼容数据目录（STEAM_COMPAT_DATA_PATH），wine 前缀位于其中的 pfx 子目录
+        prefix: String,
+        /// Steam 安装目录（STEAM_COMPAT_CLIENT_INSTALL_PATH）
+        #[serde(default)]
+        steam_dir: Option<String>,
+    },
+    /// 自定义命令模板，支持 `{exe}`、`{args}`、`{dir}` 占位符
+    ///
+    /// 例如 `gamemoderun wine {exe} {args}`；`{args}` 需单独作为一个参数。
+    Custom { template: String },
+}
+
+/// 启动器配置：运行器和额外的环境变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchRunner {
+    #[serde(flatten)]
+    pub kind: RunnerKind,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Default for LaunchRunner {
+    /// Windows 上直接运行，其他平台默认使用 PATH 中的 wine
+    fn default() -> Self {
+        let kind = if cfg!(target_os = "windows") {
+            RunnerKind::Native
+        } else {
+            RunnerKind::Wine { wine: None, prefix: None }
+        };
+        LaunchRunner { kind, env: BTreeMap::new() }
+    }
+}
+
+impl LaunchRunner {
+    /// 运行器使用的 Wine 前缀（用于查找前缀中的游戏日志），非 Wine 运行器返回 `None`
+    pub fn wine_prefix(&self) -> Option<PathBuf> {
+        match &self.kind {
+            RunnerKind::Wine { prefix, .. } => non_empty(prefix.as_deref())
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("WINEPREFIX").map(PathBuf::from))
+                .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".wine"))),
+            RunnerKind::Proton { prefix, .. } => Some(Path::new(prefix).join("pfx")),
+            _ => self.env.get("WINEPREFIX").map(PathBuf::from),
+        }
+    }
+
+    /// 构建运行 Windows 程序的命令
+    pub fn command(&self, program: &Path, args: &[String], working_dir: &Path) -> Result<Command, String> {
+        let mut command = match &self.kind {
+            RunnerKind::Native => {
+                let mut command = Command::new(program);
+                command.args(args);
+                command
+            }
+            RunnerKind::Wine { wine, prefix } => {
+                let mut command = Command::new(non_empty(wine.as_deref()).unwrap_or("wine"));
+                command.arg(program).args(args);
+                if let Some(prefix) = non_empty(prefix.as_deref()) {
+                    command.env("WINEPREFIX", prefix);
+                }
+                command
+            }
+            RunnerKind::Proton { proton, prefix, steam_dir } => {
+                if proton.trim().is_empty() || prefix.trim().is_empty() {
+                    return Err("Proton 运行器需要配置 proton 路径和兼容数据目录".to_string());
+                }
+                let steam_dir = non_empty(steam_dir.as_deref())
+                    .map(OsString::from)
+                    .or_else(default_steam_dir)
+                    .unwrap_or_default();
+                let mut command = Command::new(proton);
+                command
+                    .arg("run")
+                    .arg(program)
+                    .args(args)
+                    .env("STEAM_COMPAT_DATA_PATH", prefix)
+                    .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_dir);
+                command
+            }
+            RunnerKind::Custom { template } => expand_template(template, program, args, working_dir)?,
+        };
+        command.current_dir(working_dir).envs(&self.env);
+        Ok(command)
+    }
+
+    /// 构建执行 BAT 文件的命令
+    pub fn bat_command(&self, bat_path: &Path, working_dir: &Path) -> Result<Command, String> {
+        match &self.kind {
+            RunnerKind::Native if cfg!(target_os = "windows") => {
+                let args = vec!["/C".to_string(), "start".to_string(), String::new(), bat_path.to_string_lossy().to_string()];
+                self.command(Path::new("cmd"), &args, working_dir)
+            }
+            RunnerKind::Native => Err("当前平台无法直接执行 BAT 文件，请在设置中选择 Wine 或 Proton 运行器".to_string()),
+            // wine 的 start 支持 /unix 直接接受 Unix 路径
+            RunnerKind::Wine { .. } | RunnerKind::Proton { .. } => {
+                let args = vec!["/unix".to_string(), bat_path.to_string_lossy().to_string()];
+                self.command(Path::new("start"), &args, working_dir)
+            }
+            RunnerKind::Custom { .. } => self.command(bat_path, &[], working_dir),
+        }
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 默认 Steam 安装目录
+fn default_steam_dir() -> Option<OsString> {
+    let home = std::env::var_os("HOME")?;
+    Some(Path::new(&home).join(".steam").join("steam").into_os_string())
+}
+
+/// 展开自定义命令模板
+fn expand_template(template: &str, program: &Path, args: &[String], working_dir: &Path) -> Result<Command, String> {
+    let exe = program.to_string_lossy();
+    let dir = working_dir.to_string_lossy();
+
+    let mut parts: Vec<String> = Vec::new();
+    for token in split_command_line(template)? {
+        if token == "{args}" {
+            parts.extend(args.iter().cloned());
+        } else {
+            parts.push(token.replace("{exe}", &exe).replace("{dir}", &dir));
+        }
+    }
+
+    let (program, rest) = parts
+        .split_first()
+        .ok_or_else(|| "自定义启动命令为空".to_string())?;
+    let mut command = Command::new(program);
+    command.args(rest);
+    Ok(command)
+}
+
+/// 按空白拆分命令行，支持单引号和双引号；反斜杠只转义引号，Windows 路径可原样书写
+pub fn split_command_line(line: &str) -> Result<Vec<String>, String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut has_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (_, '\\') if matches!(chars.peek(), Some('"') | Some('\'')) => {
+                current.extend(chars.next());
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                has_token = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if has_token || !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            (None, c) => current.push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("自定义启动命令引号不匹配: {}", line));
+    }
+    if has_token || !current.is_empty() {
+        parts.push(current);
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn args_of(command: &Command) -> Vec<String> {
+        command.get_args().map(|a| a.to_string_lossy().to_string()).collect()
+    }
+
+    fn env_of(command: &Command, key: &str) -> Option<String> {
+        command
+            .get_envs()
+            .find(|(k, _)| *k == OsStr::new(key))
+            .and_then(|(_, v)| v.map(|v| v.to_string_lossy().to_string()))
+    }
+
+    fn runner(kind: RunnerKind) -> LaunchRunner {
+        LaunchRunner { kind, env: BTreeMap::new() }
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(split_command_line("  wine   game.exe -a ").unwrap(), ["wine", "game.exe", "-a"]);
+        assert!(split_command_line("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split_command_line(r#"run "C:\Program Files\Game.exe" 'a b' "" x"#).unwrap(),
+            ["run", r"C:\Program Files\Game.exe", "a b", "", "x"]
+        );
+        assert_eq!(split_command_line(r#"pre"fix suf"fix"#).unwrap(), ["prefix suffix"]);
+    }
+
+    #[test]
+    fn backslash_only_escapes_quotes() {
+        assert_eq!(split_command_line(r#"say \"hi\" C:\dir\x"#).unwrap(), [r#"say"#, r#""hi""#, r"C:\dir\x"]);
+        assert_eq!(split_command_line(r#""it\"s""#).unwrap(), [r#"it"s"#]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quote() {
+        assert!(split_command_line(r#"wine "game.exe"#).is_err());
+        assert!(split_command_line("wine 'game.exe").is_err());
+    }
+
+    #[test]
+    fn expands_template_placeholders() {
+        let args = vec!["--mode".to_string(), "a b".to_string()];
+        let command = expand_template(
+            "gamemoderun wine {exe} {args} --log={dir}/log.txt",
+            Path::new("/games/MajdataPlay.exe"),
+            &args,
+            Path::new("/games"),
+        )
+        .unwrap();
+        assert_eq!(command.get_program(), "gamemoderun");
+        assert_eq!(
+            args_of(&command),
+            ["wine", "/games/MajdataPlay.exe", "--mode", "a b", "--log=/games/log.txt"]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_template() {
+        assert!(expand_template("  ", Path::new("a.exe"), &[], Path::new(".")).is_err());
+        assert!(expand_template("{args}", Path::new("a.exe"), &[], Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn wine_runner_sets_prefix() {
+        let command = runner(RunnerKind::Wine { wine: Some("/opt/wine/bin/wine".to_string()), prefix: Some("/pfx".to_string()) })
+            .command(Path::new("game.exe"), &["-x".to_string()], Path::new("/games"))
+            .unwrap();
+        assert_eq!(command.get_program(), "/opt/wine/bin/wine");
+        assert_eq!(args_of(&command), ["game.exe", "-x"]);
+        assert_eq!(env_of(&command, "WINEPREFIX").as_deref(), Some("/pfx"));
+        assert_eq!(command.get_current_dir(), Some(Path::new("/games")));
+
+        let command = runner(RunnerKind::Wine { wine: Some(" ".to_string()), prefix: None })
+            .command(Path::new("game.exe"), &[], Path::new("/games"))
+            .unwrap();
+        assert_eq!(command.get_program(), "wine");
+        assert_eq!(env_of(&command, "WINEPREFIX"), None);
+    }
+
+    #[test]
+    fn proton_runner_sets_compat_paths() {
+        let command = runner(RunnerKind::Proton {
+            proton: "/steam/Proton 9.0/proton".to_string(),
+            prefix: "/compat".to_string(),
+            steam_dir: Some("/steam".to_string()),
+        })
+        .command(Path::new("game.exe"), &["-x".to_string()], Path::new("/games"))
+        .unwrap();
+        assert_eq!(command.get_program(), "/steam/Proton 9.0/proton");
+        assert_eq!(args_of(&command), ["run", "game.exe", "-x"]);
+        assert_eq!(env_of(&command, "STEAM_COMPAT_DATA_PATH").as_deref(), Some("/compat"));
+        assert_eq!(env_of(&command, "STEAM_COMPAT_CLIENT_INSTALL_PATH").as_deref(), Some("/steam"));
+
+        let missing = runner(RunnerKind::Proton { proton: String::new(), prefix: "/compat".to_string(), steam_dir: None });
+        assert!(missing.command(Path::new("game.exe"), &[], Path::new("/games")).is_err());
+    }
+
+    #[test]
+    fn extra_env_overrides_runner_env() {
+        let mut launch = runner(RunnerKind::Wine { wine: None, prefix: Some("/pfx".to_string()) });
+        launch.env.insert("WINEPREFIX".to_string(), "/other".to_string());
+        launch.env.insert("DXVK_HUD".to_string(), "fps".to_string());
+        let command = launch.command(Path::new("game.exe"), &[], Path::new("/games")).unwrap();
+        assert_eq!(env_of(&command, "WINEPREFIX").as_deref(), Some("/other"));
+        assert_eq!(env_of(&command, "DXVK_HUD").as_deref(), Some("fps"));
+        assert_eq!(launch.wine_prefix(), Some(PathBuf::from("/pfx")));
+    }
+
+    /// 用记录参数和环境变量的脚本代替 wine，确认实际启动的进程收到的内容
+    #[cfg(unix)]
+    #[test]
+    fn runs_stub_runner_script() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("majdatahub_runner_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stub = dir.join("wine-stub.sh");
+        std::fs::write(&stub, "#!/bin/sh\necho \"prefix=$WINEPREFIX\"\nfor arg in \"$@\"; do echo \"arg=$arg\"; done\npwd\n").unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let launch = runner(RunnerKind::Wine {
+            wine: Some(stub.to_string_lossy().to_string()),
+            prefix: Some("/stub/pfx".to_string()),
+        });
+        let output = launch
+            .command(Path::new("MajdataPlay.exe"), &["--a b".to_string()], &dir)
+            .unwrap()
+            .output()
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert!(output.status.success());
+        assert_eq!(lines[..3], ["prefix=/stub/pfx", "arg=MajdataPlay.exe", "arg=--a b"]);
+        assert!(lines[3].ends_with(&dir.file_name().unwrap().to_string_lossy().to_string()));
+    }
+}
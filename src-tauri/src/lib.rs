@@ -1,101 +1,54 @@
 // 模块声明
 mod models;
+mod api;
+mod launcher;
+mod logging;
+mod settings;
 mod commands;
+mod utils;
 
 // 导入所有命令
 use commands::*;
-use tracing_subscriber::{fmt, EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
-use std::fs::OpenOptions;
-use std::io::Write;
-
-/// 简单的线程安全文件写入器包装
-#[derive(Clone)]
-struct FileWriter {
-    file: std::sync::Arc<std::sync::Mutex<std::fs::File>>,
-}
-
-impl FileWriter {
-    fn new(file: std::fs::File) -> Self {
-        Self {
-            file: std::sync::Arc::new(std::sync::Mutex::new(file)),
-        }
-    }
-}
-
-impl Write for FileWriter {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.file.lock().unwrap().write(buf)
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.file.lock().unwrap().flush()
-    }
-}
-
-/// 初始化日志系统
-fn init_logging() {
-    // 获取可执行文件所在目录
-    let exe_path = std::env::current_exe().ok();
-    let log_dir = exe_path
-        .as_ref()
-        .and_then(|p| p.parent())
-        .unwrap_or_else(|| std::path::Path::new("."));
-    
-    let log_file_path = log_dir.join("MajdataHub.log");
-    
-    // 创建或覆盖日志文件
-    let log_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(&log_file_path)
-        .expect("无法创建日志文件");
-    
-    let file_writer = FileWriter::new(log_file);
-    
-    // 创建日志过滤器，默认 info 级别，可通过 RUST_LOG 环境变量覆盖
-    let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info"));
-    
-    tracing_subscriber::registry()
-        .with(filter)
-        .with(
-            // 输出到文件
-            fmt::layer()
-                .with_writer(move || file_writer.clone())
-                .with_target(true)  // 显示日志来源
-                .with_thread_ids(false)  // 不显示线程ID
-                .with_file(true)  // 显示文件名
-                .with_line_number(true)  // 显示行号
-                .with_ansi(false)  // 禁用颜色代码（文件不需要颜色）
-        )
-        .with(
-            // 输出到终端
-            fmt::layer()
-                .with_writer(std::io::stdout)
-                .with_target(true)  // 显示日志来源
-                .with_thread_ids(false)  // 不显示线程ID
-                .with_file(true)  // 显示文件名
-                .with_line_number(true)  // 显示行号
-                .with_ansi(true)  // 终端启用颜色代码
-        )
-        .init();
-    
-    tracing::info!("日志系统已初始化，日志文件: {:?}", log_file_path);
-}
+use tauri::Manager;
 
 /// Tauri 应用程序入口
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 初始化日志系统
-    init_logging();
+    logging::init();
     
     tracing::info!("启动 Majdata Hub 应用程序");
     
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(launcher::GameProcesses::default())
+        .manage(api::HttpClient::default())
+        .setup(|app| {
+            // 日志实时转发需要应用句柄
+            logging::stream::attach(app.handle().clone());
+
+            // 读取应用设置
+            let config_dir = app.path().app_config_dir().unwrap_or_else(|e| {
+                tracing::warn!("无法获取应用配置目录，设置保存在程序所在目录: {}", e);
+                settings::default_game_dir().with_file_name("config")
+            });
+            app.manage(settings::SettingsStore::load(&config_dir));
+
+            // 启用 API 缓存的磁盘持久化
+            match app.path().app_cache_dir() {
+                Ok(cache_dir) => api::cache::init_persistence(cache_dir),
+                Err(e) => tracing::warn!("无法获取应用缓存目录，API 缓存仅保存在内存中: {}", e),
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            // 设置相关命令
+            get_settings,
+            save_settings,
+            reset_settings,
+            import_legacy_settings,
+            get_game_paths,
             // 文件系统相关命令
             greet, 
             get_app_exe_path, 
@@ -104,7 +57,16 @@ pub fn run() {
             list_bat_files,
             execute_bat_file,
             get_launch_options,
+            save_launch_profile,
+            delete_launch_profile,
+            reset_launch_profiles,
             launch_game,
+            get_running_games,
+            collect_game_logs,
+            get_recent_logs,
+            set_log_level,
+            get_log_level,
+            set_log_stream,
             // 谱面管理相关命令
             list_chart_categories,
             list_charts_in_category,
@@ -115,6 +77,11 @@ pub fn run() {
             // 皮肤管理相关命令
             list_skins,
             delete_skin,
+            get_active_skin,
+            set_active_skin,
+            check_skin_updates,
+            validate_skin,
+            compose_skin,
             // 校验和相关命令
             calculate_checksums,
             save_checksums_to_file,
@@ -125,10 +92,15 @@ pub fn run() {
             download_file_to_path,
             fetch_remote_hashes,
             fetch_chart_list,
+            fetch_chart_detail,
             fetch_github_skins,
             download_skin_zip,
             download_charts_batch,
-            clear_api_cache
+            clear_api_cache,
+            // 缩略图和试听相关命令
+            prefetch_chart_thumbnails,
+            clear_thumbnail_cache,
+            preview_chart_track
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
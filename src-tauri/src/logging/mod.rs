@@ -0,0 +1,242 @@
+pub mod reader;
+pub mod rolling;
+pub mod stream;
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::{DefaultFields, Format, Full};
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriter};
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry, layer::SubscriberExt, util::SubscriberInitExt};
+use rolling::SizeRollingWriter;
+
+/// 日志文件名前缀
+pub const LOG_FILE_PREFIX: &str = "MajdataHub";
+
+/// 日志文件扩展名
+pub const LOG_FILE_SUFFIX: &str = "log";
+
+/// 默认保留的日志文件数量
+const DEFAULT_MAX_FILES: usize = 7;
+
+/// 按大小轮转时默认的单个文件大小上限（MB）
+const DEFAULT_MAX_SIZE_MB: u64 = 10;
+
+/// 应用标识，与 tauri.conf.json 中的 identifier 一致（日志系统在应用初始化前启动，无法通过 Tauri 获取路径）
+const APP_IDENTIFIER: &str = "com.kirisamevanilla.majdata-hub";
+
+/// 默认日志过滤规则
+const DEFAULT_FILTER: &str = "info";
+
+/// 日志目录，由 `init` 设置
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 运行时修改日志过滤规则的句柄，由 `init` 设置
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// 日志轮转方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    /// 每天一个文件：`MajdataHub.2024-01-01.log`
+    Daily,
+    /// 超过大小上限时轮转：`MajdataHub.log` -> `MajdataHub.<时间>.log`
+    Size { max_bytes: u64 },
+}
+
+/// 日志配置
+///
+/// 日志系统在应用初始化之前启动，因此通过环境变量配置：
+/// `MAJDATAHUB_LOG_ROTATION`（`daily` 或 `size`，默认 `daily`）、
+/// `MAJDATAHUB_LOG_MAX_SIZE_MB`（按大小轮转时的上限）和 `MAJDATAHUB_LOG_MAX_FILES`（保留文件数）。
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub rotation: LogRotation,
+    pub max_files: usize,
+}
+
+impl LogConfig {
+    pub fn from_env() -> Self {
+        let number = |key: &str| std::env::var(key).ok().and_then(|v| v.trim().parse::<u64>().ok());
+
+        let max_files = number("MAJDATAHUB_LOG_MAX_FILES")
+            .map(|n| n.max(1) as usize)
+            .unwrap_or(DEFAULT_MAX_FILES);
+        let rotation = match std::env::var("MAJDATAHUB_LOG_ROTATION").as_deref().map(str::trim) {
+            Ok(value) if value.eq_ignore_ascii_case("size") => LogRotation::Size {
+                max_bytes: number("MAJDATAHUB_LOG_MAX_SIZE_MB").unwrap_or(DEFAULT_MAX_SIZE_MB).max(1) * 1024 * 1024,
+            },
+            _ => LogRotation::Daily,
+        };
+        LogConfig { rotation, max_files }
+    }
+}
+
+/// 获取日志目录
+pub fn log_dir() -> Option<&'static Path> {
+    LOG_DIR.get().map(|p| p.as_path())
+}
+
+/// 列出目录中本工具的日志文件，按修改时间从新到旧排列
+pub fn list_log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| {
+                    let name = e.file_name();
+                    let name = name.to_string_lossy();
+                    name.starts_with(LOG_FILE_PREFIX) && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+                })
+                .filter_map(|e| {
+                    let meta = e.metadata().ok().filter(|m| m.is_file())?;
+                    Some((meta.modified().ok()?, e.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// 当前日志目录中的日志文件，从新到旧
+pub fn log_files() -> Vec<PathBuf> {
+    log_dir().map(list_log_files).unwrap_or_default()
+}
+
+/// 创建文件日志写入器
+fn file_writer(dir: &Path, config: &LogConfig) -> std::io::Result<BoxMakeWriter> {
+    match config.rotation {
+        LogRotation::Daily => {
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_FILE_PREFIX)
+                .filename_suffix(LOG_FILE_SUFFIX)
+                .max_log_files(config.max_files)
+                .build(dir)
+                .map_err(std::io::Error::other)?;
+            Ok(BoxMakeWriter::new(appender))
+        }
+        LogRotation::Size { max_bytes } => {
+            let writer = SizeRollingWriter::new(dir, max_bytes, config.max_files)?;
+            Ok(BoxMakeWriter::new(move || writer.clone()))
+        }
+    }
+}
+
+/// 写入日志文件的格式层，格式需与 [`reader`] 的解析规则保持一致
+fn file_layer<S, W>(writer: W) -> fmt::Layer<S, DefaultFields, Format<Full>, W>
+where
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    fmt::layer()
+        .with_writer(writer)
+        .with_target(true)  // 显示日志来源
+        .with_thread_ids(false)  // 不显示线程ID
+        .with_file(true)  // 显示文件名
+        .with_line_number(true)  // 显示行号
+        .with_ansi(false)  // 禁用颜色代码（文件不需要颜色）
+}
+
+/// 运行时修改日志过滤规则
+///
+/// 支持单个级别（`debug`）或 `RUST_LOG` 格式的规则（`info,majdata_hub_lib=trace`），返回生效后的规则。
+pub fn set_filter(directives: &str) -> Result<String, String> {
+    let filter = EnvFilter::try_new(directives.trim())
+        .map_err(|e| format!("无效的日志级别 {}: {}", directives, e))?;
+    let applied = filter.to_string();
+    FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "日志系统未初始化".to_string())?
+        .reload(filter)
+        .map_err(|e| format!("修改日志级别失败: {}", e))?;
+    Ok(applied)
+}
+
+/// 当前日志过滤规则
+pub fn current_filter() -> Option<String> {
+    FILTER_HANDLE.get()?.with_current(|filter| filter.to_string()).ok()
+}
+
+/// 应用日志目录，规则与 Tauri 的 `app_log_dir` 相同
+///
+/// macOS 上为 `~/Library/Logs/<identifier>`，其他平台为本地数据目录下的 `<identifier>/logs`
+/// （Windows 上位于 `%LOCALAPPDATA%`）。日志系统在 Tauri 初始化前启动，因此自行计算；
+/// 游戏输出记录和日志打包也使用此目录，保证位置一致。
+pub fn app_log_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|dir| dir.join("Library/Logs").join(APP_IDENTIFIER))
+    } else {
+        dirs::data_local_dir().map(|dir| dir.join(APP_IDENTIFIER).join("logs"))
+    }
+}
+
+/// 日志目录候选：程序所在目录 -> 应用日志目录 -> 临时目录
+fn candidate_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        candidates.push(dir);
+    }
+    candidates.extend(app_log_dir());
+    candidates.push(std::env::temp_dir().join(APP_IDENTIFIER).join("logs"));
+    candidates
+}
+
+/// 依次尝试候选目录，返回第一个可写的目录和写入器，以及前面失败的原因
+fn open_log_dir(config: &LogConfig) -> (Option<(PathBuf, BoxMakeWriter)>, Vec<String>) {
+    let mut failures = Vec::new();
+    for dir in candidate_dirs() {
+        let result = std::fs::create_dir_all(&dir).and_then(|_| file_writer(&dir, config));
+        match result {
+            Ok(writer) => return (Some((dir, writer)), failures),
+            Err(e) => failures.push(format!("{:?}: {}", dir, e)),
+        }
+    }
+    (None, failures)
+}
+
+/// 初始化日志系统
+///
+/// 日志文件无法创建时只输出到终端，不影响程序启动。
+pub fn init() {
+    let config = LogConfig::from_env();
+    let (opened, failures) = open_log_dir(&config);
+    let (log_dir, file_writer) = opened.unzip();
+
+    // 创建日志过滤器，默认 info 级别，可通过 RUST_LOG 环境变量覆盖，运行时可通过 set_filter 修改
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (filter, filter_handle) = reload::Layer::new(filter);
+
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        // 输出到文件
+        .with(file_writer.map(file_layer))
+        .with(
+            // 输出到终端
+            fmt::layer()
+                .with_writer(std::io::stdout)
+                .with_target(true)  // 显示日志来源
+                .with_thread_ids(false)  // 不显示线程ID
+                .with_file(true)  // 显示文件名
+                .with_line_number(true)  // 显示行号
+                .with_ansi(true)  // 终端启用颜色代码
+        )
+        // 转发到前端调试页面
+        .with(stream::EventForwardLayer);
+    if let Err(e) = subscriber.try_init() {
+        eprintln!("初始化日志系统失败: {}", e);
+        return;
+    }
+    let _ = FILTER_HANDLE.set(filter_handle);
+
+    for failure in &failures {
+        tracing::warn!("无法在此目录创建日志文件，尝试下一个位置: {}", failure);
+    }
+    match log_dir {
+        Some(dir) => {
+            tracing::info!("日志系统已初始化，日志目录: {:?}，轮转: {:?}，保留 {} 个文件", dir, config.rotation, config.max_files);
+            let _ = LOG_DIR.set(dir);
+        }
+        None => tracing::error!("无法创建日志文件，日志仅输出到终端"),
+    }
+}
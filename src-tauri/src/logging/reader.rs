@@ -0,0 +1,250 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use tracing::Level;
+
+/// 每个日志文件最多读取的字节数（只读取末尾部分）
+const MAX_READ_BYTES: u64 = 8 * 1024 * 1024;
+
+/// 解析后的日志条目
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    /// 日志位置（`src/xxx.rs:12`），可能为空
+    pub location: Option<String>,
+    /// 日志内容，多行日志的后续行也包含在内
+    pub message: String,
+}
+
+/// 日志查询条件
+pub struct LogQuery {
+    /// 最低级别，例如 WARN 只返回 WARN 和 ERROR
+    pub level: Option<Level>,
+    pub since: Option<DateTime<FixedOffset>>,
+    pub until: Option<DateTime<FixedOffset>>,
+    pub limit: usize,
+}
+
+impl LogQuery {
+    fn matches(&self, entry: &LogEntry, time: &DateTime<FixedOffset>) -> bool {
+        if let Some(min) = self.level {
+            match entry.level.parse::<Level>() {
+                // tracing 中越详细的级别越“大”
+                Ok(level) if level <= min => {}
+                _ => return false,
+            }
+        }
+        self.since.is_none_or(|since| *time >= since) && self.until.is_none_or(|until| *time <= until)
+    }
+}
+
+/// 解析一行日志：`<时间> <级别> [<span>{<字段>}:...: ]<target>: <文件>:<行号>: <内容>`
+///
+/// 与实时日志一致，span 上下文不保留。不是以时间和级别开头的行返回 None（属于上一条日志的后续行）。
+fn parse_line(line: &str) -> Option<(DateTime<FixedOffset>, LogEntry)> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    let time = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+    level.parse::<Level>().ok()?;
+
+    let (target, location, message) = split_located(rest).unwrap_or_else(|| match rest.split_once(": ") {
+        Some((target, message)) if !target.contains(' ') => (target, None, message),
+        _ => ("", None, rest),
+    });
+
+    let entry = LogEntry {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        location: location.map(str::to_string),
+        message: message.to_string(),
+    };
+    Some((time, entry))
+}
+
+/// 按 `文件:行号` 定位 target 和内容
+///
+/// span 字段中可能含有 `: `，因此从位置信息往前找：其前面最后一个空格之后是 target，再前面是 span 列表。
+fn split_located(rest: &str) -> Option<(&str, Option<&str>, &str)> {
+    rest.match_indices(": ").find_map(|(index, _)| {
+        let (location, message) = rest[index + 2..].split_once(": ")?;
+        if !is_location(location) {
+            return None;
+        }
+        let head = &rest[..index];
+        let target = head.rsplit_once(' ').map_or(head, |(_, target)| target);
+        Some((target, Some(location), message))
+    })
+}
+
+/// 是否为 `文件:行号` 形式
+fn is_location(text: &str) -> bool {
+    text.rsplit_once(':')
+        .is_some_and(|(file, line)| file.ends_with(".rs") && line.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 读取日志文件末尾
+fn read_tail(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    if len > MAX_READ_BYTES {
+        file.seek(SeekFrom::Start(len - MAX_READ_BYTES))?;
+    }
+    let mut bytes = Vec::new();
+    file.take(MAX_READ_BYTES).read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 解析日志文件中的所有条目
+fn parse_file(path: &Path) -> std::io::Result<Vec<(DateTime<FixedOffset>, LogEntry)>> {
+    let text = read_tail(path)?;
+    let mut entries: Vec<(DateTime<FixedOffset>, LogEntry)> = Vec::new();
+    for line in text.lines() {
+        match parse_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                // 后续行追加到上一条；文件开头被截断的残行直接丢弃
+                if let Some((_, last)) = entries.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// 从日志文件（按从新到旧排列）中读取满足条件的最近日志，按时间先后返回
+pub fn read_recent(files: &[impl AsRef<Path>], query: &LogQuery) -> Vec<LogEntry> {
+    let mut result: Vec<(DateTime<FixedOffset>, LogEntry)> = Vec::new();
+    for path in files {
+        let entries = match parse_file(path.as_ref()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("读取日志文件失败 {:?}: {}", path.as_ref(), e);
+                continue;
+            }
+        };
+        result.extend(entries.into_iter().filter(|(time, entry)| query.matches(entry, time)));
+        if result.len() >= query.limit {
+            break;
+        }
+    }
+
+    result.sort_by_key(|(time, _)| *time);
+    let skip = result.len().saturating_sub(query.limit);
+    result.into_iter().skip(skip).map(|(_, entry)| entry).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// 收集格式化输出的写入器
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// 用日志文件的格式层输出 `f` 中记录的日志
+    fn format_logs(f: impl FnOnce()) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(super::super::file_layer(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, f);
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    fn parse_all(text: &str) -> Vec<LogEntry> {
+        text.lines().filter_map(parse_line).map(|(_, entry)| entry).collect()
+    }
+
+    #[test]
+    fn parses_plain_event() {
+        let line = line!() + 1;
+        let text = format_logs(|| tracing::info!("下载完成: {}", "skin.zip"));
+        let entries = parse_all(&text);
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.level, "INFO");
+        assert_eq!(entry.target, module_path!());
+        assert_eq!(entry.location, Some(format!("{}:{}", file!(), line)));
+        assert_eq!(entry.message, "下载完成: skin.zip");
+    }
+
+    #[test]
+    fn skips_span_context() {
+        let text = format_logs(|| {
+            let outer = tracing::info_span!("download", url = "https://example.com/a: b");
+            let _outer = outer.enter();
+            let inner = tracing::info_span!("extract");
+            let _inner = inner.enter();
+            tracing::warn!(count = 3, "解压失败: {}", "bad zip");
+        });
+        assert!(text.contains("download{"), "fmt 输出应包含 span: {}", text);
+        let entries = parse_all(&text);
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.target, module_path!());
+        assert!(entry.location.as_deref().is_some_and(is_location));
+        assert_eq!(entry.message, "解压失败: bad zip count=3");
+    }
+
+    #[test]
+    fn custom_target_without_module_path() {
+        let text = format_logs(|| tracing::error!(target: "majdata_hub", "启动失败"));
+        let entries = parse_all(&text);
+        assert_eq!(entries[0].target, "majdata_hub");
+        assert_eq!(entries[0].level, "ERROR");
+        assert_eq!(entries[0].message, "启动失败");
+    }
+
+    #[test]
+    fn falls_back_without_location() {
+        let (_, entry) = parse_line("2024-05-01T12:00:00.123456Z  INFO tauri::app: 窗口已创建").unwrap();
+        assert_eq!(entry.target, "tauri::app");
+        assert_eq!(entry.location, None);
+        assert_eq!(entry.message, "窗口已创建");
+    }
+
+    #[test]
+    fn continuation_lines_are_not_entries() {
+        assert!(parse_line("  at MajdataPlay.Game.GamePlayManager.Start ()").is_none());
+        assert!(parse_line("").is_none());
+    }
+
+    #[test]
+    fn multiline_message_is_joined() {
+        let text = format_logs(|| {
+            tracing::error!("游戏输出:\nline 1\nline 2");
+            tracing::info!("下一条");
+        });
+        let dir = std::env::temp_dir().join(format!("majdata-hub-reader-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("MajdataHub.log");
+        std::fs::write(&path, &text).unwrap();
+        let query = LogQuery { level: Some(Level::WARN), since: None, until: None, limit: 10 };
+        let entries = read_recent(&[&path], &query);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "游戏输出:\nline 1\nline 2");
+    }
+}
@@ -0,0 +1,93 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use super::{list_log_files, LOG_FILE_PREFIX, LOG_FILE_SUFFIX};
+
+/// 按大小轮转的日志写入器
+///
+/// 当前日志写入 `MajdataHub.log`，超过大小上限后重命名为 `MajdataHub.<时间>.log` 并新建文件，
+/// 只保留最近的 `max_files` 个日志文件。
+#[derive(Clone)]
+pub struct SizeRollingWriter {
+    state: Arc<Mutex<RollingState>>,
+}
+
+struct RollingState {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl SizeRollingWriter {
+    /// 以追加方式打开当前日志文件（不会清空上次运行的日志）
+    pub fn new(dir: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let file = open_current(dir)?;
+        let size = file.metadata()?.len();
+        let state = RollingState { dir: dir.to_path_buf(), file, size, max_bytes, max_files };
+        Ok(Self { state: Arc::new(Mutex::new(state)) })
+    }
+
+    /// 当前日志文件路径
+    pub fn current_path(dir: &Path) -> PathBuf {
+        dir.join(format!("{}.{}", LOG_FILE_PREFIX, LOG_FILE_SUFFIX))
+    }
+}
+
+fn open_current(dir: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(SizeRollingWriter::current_path(dir))
+}
+
+impl RollingState {
+    fn rotate(&mut self) -> io::Result<()> {
+        let current = SizeRollingWriter::current_path(&self.dir);
+        let rotated = self.dir.join(format!(
+            "{}.{}.{}",
+            LOG_FILE_PREFIX,
+            chrono::Local::now().format("%Y-%m-%d_%H%M%S%.3f"),
+            LOG_FILE_SUFFIX
+        ));
+        self.file.flush()?;
+        std::fs::rename(&current, &rotated)?;
+        self.file = open_current(&self.dir)?;
+        self.size = 0;
+        self.prune();
+        Ok(())
+    }
+
+    /// 删除超出保留数量的旧日志（当前文件也计入数量）
+    fn prune(&self) {
+        let current = SizeRollingWriter::current_path(&self.dir);
+        let old = list_log_files(&self.dir)
+            .into_iter()
+            .filter(|path| *path != current)
+            .skip(self.max_files.saturating_sub(1));
+        for path in old {
+            // 日志系统内部不能再写日志，失败时忽略
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl Write for SizeRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().map_err(|_| io::Error::other("日志写入器锁已损坏"))?;
+        if state.size > 0 && state.size + buf.len() as u64 > state.max_bytes {
+            // 轮转失败时继续写入当前文件
+            let _ = state.rotate();
+        }
+        let written = state.file.write(buf)?;
+        state.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut state = self.state.lock().map_err(|_| io::Error::other("日志写入器锁已损坏"))?;
+        state.file.flush()
+    }
+}
@@ -0,0 +1,88 @@
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::Emitter;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use super::reader::LogEntry;
+
+/// 后端日志事件名
+pub const LOG_EVENT: &str = "backend-log";
+
+/// 用于发送事件的应用句柄，在应用初始化后设置
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// 是否转发日志（由调试页面开启，避免没有页面查看时产生大量事件）
+static STREAM_ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// 发送事件的过程中产生的日志不再转发，防止递归
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 设置应用句柄，之后的日志才能转发到前端
+pub fn attach(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// 开启或关闭日志转发
+pub fn set_enabled(enabled: bool) {
+    STREAM_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 收集日志字段：`message` 作为内容，其他字段以 `key=value` 追加在后面
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// 将日志记录以 Tauri 事件（`backend-log`）转发给前端的 tracing 层
+pub struct EventForwardLayer;
+
+impl<S: Subscriber> Layer<S> for EventForwardLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if !STREAM_ENABLED.load(Ordering::Relaxed) || FORWARDING.with(Cell::get) {
+            return;
+        }
+        let Some(app) = APP_HANDLE.get() else {
+            return;
+        };
+
+        let meta = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            level: meta.level().to_string(),
+            target: meta.target().to_string(),
+            location: meta.file().zip(meta.line()).map(|(file, line)| format!("{}:{}", file, line)),
+            message: visitor.message + &visitor.fields,
+        };
+
+        FORWARDING.with(|f| f.set(true));
+        // 发送失败时无法再记录日志，直接忽略
+        let _ = app.emit(LOG_EVENT, &entry);
+        FORWARDING.with(|f| f.set(false));
+    }
+}
@@ -6,3 +6,58 @@ pub struct FileChecksum {
     pub file_path: String,
     pub checksum: String,
 }
+
+/// 谱面元数据 sidecar 文件名（位于谱面文件夹内）
+pub const CHART_METADATA_FILE: &str = "majdatahub.json";
+
+/// 谱面元数据，下载时写入谱面文件夹，保留原始标题
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChartMetadata {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "folderName")]
+    pub folder_name: String,
+    #[serde(rename = "downloadedAt")]
+    pub downloaded_at: String,
+}
+
+/// 皮肤清单文件名（位于皮肤文件夹根目录）
+pub const SKIN_MANIFEST_FILE: &str = "skin.json";
+
+/// 皮肤清单（可选），提供作者、版本、简介和预览图
+///
+/// 本地皮肤中 `preview` 为预览图的绝对路径；在线皮肤中为预览图 URL。
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SkinManifest {
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub preview: Option<String>,
+}
+
+/// 皮肤安装记录文件名（位于皮肤文件夹根目录）
+pub const SKIN_INSTALL_FILE: &str = "majdatahub.json";
+
+/// 皮肤安装记录，下载解压时写入，用于检测上游是否更新
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SkinInstallRecord {
+    /// 上游 zip 文件名
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    /// 来源名称
+    #[serde(default)]
+    pub source: String,
+    #[serde(rename = "downloadUrl")]
+    pub download_url: String,
+    /// GitHub blob SHA（其他来源可能没有）
+    #[serde(default)]
+    pub sha: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(rename = "installedAt")]
+    pub installed_at: String,
+}
@@ -0,0 +1,198 @@
+use std::path::{Component, Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/// 文件夹名的最大字节数（UTF-8），为 Windows 路径长度留出余量
+const MAX_FILE_NAME_BYTES: usize = 180;
+
+/// Windows 保留的设备名（不区分大小写，带扩展名同样无效）
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 将非法字符映射为对应的全角字符，尽量保留标题的可读性
+fn replace_illegal_char(c: char) -> Option<char> {
+    match c {
+        '/' => Some('／'),
+        '\\' => Some('＼'),
+        ':' => Some('：'),
+        '*' => Some('＊'),
+        '?' => Some('？'),
+        '"' => Some('＂'),
+        '<' => Some('＜'),
+        '>' => Some('＞'),
+        '|' => Some('｜'),
+        c if c.is_control() => None,
+        c => Some(c),
+    }
+}
+
+/// 按字节数截断字符串，保证不会截断在字符中间
+fn truncate_to_bytes(name: &str, max_bytes: usize) -> &str {
+    if name.len() <= max_bytes {
+        return name;
+    }
+    let mut end = max_bytes;
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    &name[..end]
+}
+
+/// 去掉结尾的空白和 `.`（Windows 不允许文件名以它们结尾，交替出现时也要全部去掉）
+fn trim_name_end(name: &str) -> &str {
+    name.trim_end_matches(|c: char| c == '.' || c.is_whitespace())
+}
+
+/// 判断是否为 Windows 保留设备名（如 CON、nul.txt、COM1）
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or("").trim_end();
+    RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem))
+}
+
+/// 将任意字符串转换为在 Windows / macOS / Linux 上都安全的文件夹名
+///
+/// 处理规则：
+/// - Unicode NFC 规范化，避免同一标题在不同系统上得到不同的文件夹
+/// - 非法字符替换为全角字符，控制字符直接移除
+/// - 去掉首尾空格和结尾的 `.`
+/// - Windows 保留名前加 `_`
+/// - 超长名称按字节截断
+///
+/// 结果为空时返回 `None`，由调用方决定回退名称。
+/// 相同输入总是得到相同输出。
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let normalized: String = name
+        .nfc()
+        .filter_map(replace_illegal_char)
+        .collect();
+
+    let trimmed = trim_name_end(normalized.trim());
+    let truncated = trim_name_end(truncate_to_bytes(trimmed, MAX_FILE_NAME_BYTES));
+
+    if truncated.is_empty() {
+        return None;
+    }
+
+    if is_reserved_name(truncated) {
+        return Some(format!("_{}", truncated));
+    }
+
+    Some(truncated.to_string())
+}
+
+/// 将不可信的相对路径（如下载内容中的清单字段）解析为 `base` 目录内已存在的路径
+///
+/// 绝对路径、盘符以及包含 `..`、`.` 的路径直接拒绝；解析符号链接后仍必须位于 `base` 内。
+/// 返回 `base.join(relative)`，不使用规范化后的路径（Windows 上会带 `\\?\` 前缀）。
+pub fn resolve_within(base: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    let mut components = relative.components().peekable();
+    components.peek()?;
+    if !components.all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let path = base.join(relative);
+    let canonical_base = base.canonicalize().ok()?;
+    let canonical = path.canonicalize().ok()?;
+    canonical.starts_with(&canonical_base).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_illegal_characters() {
+        assert_eq!(sanitize_file_name("a/b\\c:d*e?f\"g<h>i|j").as_deref(), Some("a／b＼c：d＊e？f＂g＜h＞i｜j"));
+        assert_eq!(sanitize_file_name("tab\there\n").as_deref(), Some("tabhere"));
+    }
+
+    #[test]
+    fn prefixes_reserved_names() {
+        assert_eq!(sanitize_file_name("CON").as_deref(), Some("_CON"));
+        assert_eq!(sanitize_file_name("nul.txt").as_deref(), Some("_nul.txt"));
+        assert_eq!(sanitize_file_name("com1 .mp3").as_deref(), Some("_com1 .mp3"));
+        assert_eq!(sanitize_file_name("LPT9").as_deref(), Some("_LPT9"));
+        assert_eq!(sanitize_file_name("CONSOLE").as_deref(), Some("CONSOLE"));
+        assert_eq!(sanitize_file_name("COM10").as_deref(), Some("COM10"));
+    }
+
+    #[test]
+    fn trims_spaces_and_trailing_dots() {
+        assert_eq!(sanitize_file_name("  title  ").as_deref(), Some("title"));
+        assert_eq!(sanitize_file_name("title. . .").as_deref(), Some("title"));
+        assert_eq!(sanitize_file_name(".hidden").as_deref(), Some(".hidden"));
+    }
+
+    #[test]
+    fn empty_results_are_none() {
+        assert_eq!(sanitize_file_name(""), None);
+        assert_eq!(sanitize_file_name("   "), None);
+        assert_eq!(sanitize_file_name(".."), None);
+        assert_eq!(sanitize_file_name("\u{0007}\u{0008}"), None);
+    }
+
+    #[test]
+    fn normalizes_to_nfc() {
+        let decomposed = "Cafe\u{0301}";
+        let composed = "Caf\u{00e9}";
+        assert_eq!(sanitize_file_name(decomposed).as_deref(), Some(composed));
+        assert_eq!(sanitize_file_name(decomposed), sanitize_file_name(composed));
+    }
+
+    #[test]
+    fn truncates_long_names_by_bytes() {
+        let name = "a".repeat(MAX_FILE_NAME_BYTES + 20);
+        assert_eq!(sanitize_file_name(&name).map(|n| n.len()), Some(MAX_FILE_NAME_BYTES));
+    }
+
+    #[test]
+    fn truncation_never_splits_a_character() {
+        // 3 字节字符，180 不是 3 的倍数时需要回退到字符边界
+        let name = format!("a{}", "谱".repeat(MAX_FILE_NAME_BYTES));
+        let sanitized = sanitize_file_name(&name).unwrap();
+        assert!(sanitized.len() <= MAX_FILE_NAME_BYTES);
+        assert!(sanitized.starts_with('a'));
+        assert_eq!(sanitized.len(), 1 + (MAX_FILE_NAME_BYTES - 1) / 3 * 3);
+
+        let emoji = "🎵".repeat(MAX_FILE_NAME_BYTES);
+        let sanitized = sanitize_file_name(&emoji).unwrap();
+        assert!(sanitized.len() <= MAX_FILE_NAME_BYTES);
+        assert!(sanitized.chars().all(|c| c == '🎵'));
+    }
+
+    #[test]
+    fn resolve_within_accepts_only_relative_paths_inside_base() {
+        let base = std::env::temp_dir().join(format!("majdatahub_resolve_{}", std::process::id()));
+        std::fs::create_dir_all(base.join("sub")).unwrap();
+        std::fs::write(base.join("sub").join("preview.png"), b"png").unwrap();
+        std::fs::write(base.with_extension("txt"), b"outside").unwrap();
+        let outside_name = base.with_extension("txt").file_name().unwrap().to_string_lossy().to_string();
+
+        assert_eq!(resolve_within(&base, "sub/preview.png"), Some(base.join("sub/preview.png")));
+        assert_eq!(resolve_within(&base, "sub/missing.png"), None);
+        assert_eq!(resolve_within(&base, ""), None);
+        assert_eq!(resolve_within(&base, "./sub/preview.png"), None);
+        assert_eq!(resolve_within(&base, &format!("../{}", outside_name)), None);
+        assert_eq!(resolve_within(&base, "sub/../sub/preview.png"), None);
+        let absolute = base.join("sub").join("preview.png").to_string_lossy().to_string();
+        assert_eq!(resolve_within(&base, &absolute), None);
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(base.with_extension("txt"), base.join("link.txt")).unwrap();
+            assert_eq!(resolve_within(&base, "link.txt"), None);
+        }
+
+        std::fs::remove_dir_all(&base).unwrap();
+        std::fs::remove_file(base.with_extension("txt")).unwrap();
+    }
+
+    #[test]
+    fn truncation_trims_exposed_trailing_dots() {
+        let name = format!("{}. tail", "a".repeat(MAX_FILE_NAME_BYTES - 2));
+        assert_eq!(sanitize_file_name(&name), Some("a".repeat(MAX_FILE_NAME_BYTES - 2)));
+    }
+}
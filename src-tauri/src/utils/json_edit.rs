@@ -0,0 +1,238 @@
+use std::ops::Range;
+
+/// JSON 文本扫描器，只定位值的字节范围，不构建完整的值
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, b: u8) -> Option<()> {
+        self.skip_ws();
+        if self.peek()? == b {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// 读取字符串，返回原始字节范围（含引号）
+    fn string(&mut self) -> Option<Range<usize>> {
+        self.skip_ws();
+        let start = self.pos;
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        while self.pos < self.bytes.len() {
+            match self.bytes[self.pos] {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(start..self.pos);
+                }
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+
+    /// 跳过任意值，返回其字节范围
+    fn value(&mut self) -> Option<Range<usize>> {
+        self.skip_ws();
+        let start = self.pos;
+        match self.peek()? {
+            b'"' => {
+                self.string()?;
+            }
+            b'{' => {
+                self.pos += 1;
+                self.skip_ws();
+                if self.peek()? == b'}' {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.string()?;
+                        self.expect(b':')?;
+                        self.value()?;
+                        self.skip_ws();
+                        match self.peek()? {
+                            b',' => self.pos += 1,
+                            b'}' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return None,
+                        }
+                    }
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                self.skip_ws();
+                if self.peek()? == b']' {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.value()?;
+                        self.skip_ws();
+                        match self.peek()? {
+                            b',' => self.pos += 1,
+                            b']' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return None,
+                        }
+                    }
+                }
+            }
+            _ => {
+                // 数字、true、false、null
+                while self.pos < self.bytes.len()
+                    && !matches!(self.bytes[self.pos], b',' | b'}' | b']')
+                    && !self.bytes[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return None;
+                }
+            }
+        }
+        Some(start..self.pos)
+    }
+
+    /// 在当前位置的对象中查找键，返回其值的字节范围
+    fn find_in_object(&mut self, key: &str) -> Option<Range<usize>> {
+        self.expect(b'{')?;
+        self.skip_ws();
+        if self.peek()? == b'}' {
+            return None;
+        }
+        loop {
+            let key_range = self.string()?;
+            let key_text: String = serde_json::from_slice(&self.bytes[key_range]).ok()?;
+            self.expect(b':')?;
+            let value_range = self.value()?;
+            if key_text == key {
+                return Some(value_range);
+            }
+            self.skip_ws();
+            match self.peek()? {
+                b',' => self.pos += 1,
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// 查找 `path` 指向的值在文本中的字节范围
+pub fn find_value_span(text: &str, path: &[&str]) -> Option<Range<usize>> {
+    // 跳过 UTF-8 BOM（.NET 写出的配置文件常带 BOM）
+    let start = if text.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+    let mut span = start..text.len();
+    for key in path {
+        let mut scanner = Scanner { bytes: text.as_bytes(), pos: span.start };
+        span = scanner.find_in_object(key)?;
+    }
+    Some(span)
+}
+
+/// 将 `path` 指向的值替换为 `new_value`（已序列化的 JSON）
+///
+/// 只替换该值的文本，缩进、键顺序和未知字段等其余内容保持不变。
+pub fn replace_value(text: &str, path: &[&str], new_value: &str) -> Result<String, String> {
+    let span = find_value_span(text, path)
+        .ok_or_else(|| format!("未找到字段: {}", path.join(".")))?;
+    let mut result = String::with_capacity(text.len() + new_value.len());
+    result.push_str(&text[..span.start]);
+    result.push_str(new_value);
+    result.push_str(&text[span.end..]);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r#"{
+    "Game": {
+        "Speed": 7.5,
+        "Skin": "not this one"
+    },
+    "Display": {
+        "Resolution": [1920, 1080],
+        "Skin": "Default",
+        "FullScreen": true
+    },
+    "Unknown": { "Nested": [ { "a": null } ] }
+}
+"#;
+
+    #[test]
+    fn replaces_nested_value_only() {
+        let result = replace_value(SETTINGS, &["Display", "Skin"], "\"Custom\"").unwrap();
+        assert_eq!(result, SETTINGS.replacen("\"Default\"", "\"Custom\"", 1));
+        assert_eq!(find_value_span(&result, &["Game", "Skin"]).map(|s| &result[s]), Some("\"not this one\""));
+    }
+
+    #[test]
+    fn handles_utf8_bom() {
+        let text = format!("\u{feff}{}", SETTINGS);
+        let span = find_value_span(&text, &["Display", "Skin"]).unwrap();
+        assert_eq!(&text[span], "\"Default\"");
+        let result = replace_value(&text, &["Display", "Skin"], "\"Custom\"").unwrap();
+        assert!(result.starts_with('\u{feff}'));
+        assert_eq!(result, text.replacen("\"Default\"", "\"Custom\"", 1));
+    }
+
+    #[test]
+    fn handles_escaped_quotes_and_backslashes() {
+        let text = r#"{"a\"b": "x\\", "Path": "C:\\Games\\\"Maj\"", "Skin": "v"}"#;
+        assert_eq!(find_value_span(text, &["a\"b"]).map(|s| &text[s]), Some(r#""x\\""#));
+        assert_eq!(find_value_span(text, &["Path"]).map(|s| &text[s]), Some(r#""C:\\Games\\\"Maj\"""#));
+        let result = replace_value(text, &["Skin"], r#""new \"skin\"""#).unwrap();
+        assert_eq!(result, r#"{"a\"b": "x\\", "Path": "C:\\Games\\\"Maj\"", "Skin": "new \"skin\""}"#);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["Skin"], "new \"skin\"");
+    }
+
+    #[test]
+    fn missing_key_is_an_error() {
+        assert!(find_value_span(SETTINGS, &["Display", "Missing"]).is_none());
+        assert!(find_value_span(SETTINGS, &["Missing", "Skin"]).is_none());
+        assert!(replace_value(SETTINGS, &["Display", "Missing"], "1").is_err());
+    }
+
+    #[test]
+    fn empty_object_has_no_keys() {
+        assert!(find_value_span("{}", &["Display"]).is_none());
+        assert!(find_value_span(r#"{ "Display": {} }"#, &["Display", "Skin"]).is_none());
+        assert!(replace_value("{ }", &["Skin"], "\"a\"").is_err());
+    }
+
+    #[test]
+    fn non_object_values_are_not_descended() {
+        assert!(find_value_span("[1, 2]", &["Skin"]).is_none());
+        assert!(find_value_span(r#"{"Display": "Skin"}"#, &["Display", "Skin"]).is_none());
+        assert!(find_value_span(r#"{"Display": [{"Skin": 1}]}"#, &["Display", "Skin"]).is_none());
+        assert!(find_value_span("null", &["Skin"]).is_none());
+    }
+
+    #[test]
+    fn truncated_text_is_rejected() {
+        assert!(find_value_span(r#"{"Display": {"Skin": "Def"#, &["Display", "Skin"]).is_none());
+    }
+}
@@ -0,0 +1,207 @@
+/// 谱面文件夹中可识别的背景图扩展名
+pub const IMAGE_EXTENSIONS: [&str; 4] = ["jpg", "png", "webp", "bmp"];
+
+/// 谱面文件夹中可识别的音频扩展名
+pub const AUDIO_EXTENSIONS: [&str; 4] = ["mp3", "ogg", "wav", "flac"];
+
+/// 谱面文件夹中可识别的视频扩展名
+pub const VIDEO_EXTENSIONS: [&str; 2] = ["mp4", "webm"];
+
+/// 下载文件的期望类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Audio,
+    Video,
+    Text,
+}
+
+impl MediaKind {
+    /// 无法识别具体格式时使用的默认扩展名
+    fn default_extension(self) -> &'static str {
+        match self {
+            MediaKind::Image => "jpg",
+            MediaKind::Audio => "mp3",
+            MediaKind::Video => "mp4",
+            MediaKind::Text => "txt",
+        }
+    }
+
+    /// 该类型可接受的扩展名
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            MediaKind::Image => &IMAGE_EXTENSIONS,
+            MediaKind::Audio => &AUDIO_EXTENSIONS,
+            MediaKind::Video => &VIDEO_EXTENSIONS,
+            MediaKind::Text => &["txt"],
+        }
+    }
+}
+
+/// 根据文件头（magic bytes）识别格式，返回扩展名
+fn sniff_magic(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some("jpg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("png"),
+        [b'B', b'M', ..] => Some("bmp"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("webp"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("wav"),
+        [b'O', b'g', b'g', b'S', ..] => Some("ogg"),
+        [b'f', b'L', b'a', b'C', ..] => Some("flac"),
+        [b'I', b'D', b'3', ..] => Some("mp3"),
+        // MPEG 音频帧同步字（无 ID3 标签的 mp3）
+        [0xFF, b, ..] if b & 0xE0 == 0xE0 => Some("mp3"),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("mp4"),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => Some("webm"),
+        _ => None,
+    }
+}
+
+/// 根据 Content-Type 推断扩展名
+fn extension_from_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/webp" => Some("webp"),
+        "image/bmp" | "image/x-ms-bmp" => Some("bmp"),
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/ogg" | "application/ogg" | "audio/vorbis" => Some("ogg"),
+        "audio/wav" | "audio/x-wav" | "audio/wave" => Some("wav"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        "video/mp4" => Some("mp4"),
+        "video/webm" => Some("webm"),
+        "text/plain" => Some("txt"),
+        _ => None,
+    }
+}
+
+/// 判断响应是否为 HTML / JSON（通常是错误页或 API 错误信息）
+fn looks_like_markup(content_type: Option<&str>, bytes: &[u8]) -> bool {
+    if let Some(ct) = content_type {
+        let ct = ct.to_ascii_lowercase();
+        if ct.contains("text/html") || ct.contains("application/json") || ct.contains("application/problem+json") {
+            return true;
+        }
+    }
+
+    // 跳过 UTF-8 BOM 和前导空白后检查首字符
+    let body = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    let start = body.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(body.len());
+    let head = &body[start..body.len().min(start + 64)];
+    let head_lower = head.to_ascii_lowercase();
+    head_lower.starts_with(b"<!doctype html")
+        || head_lower.starts_with(b"<html")
+        || head_lower.starts_with(b"{\"")
+        || head_lower.starts_with(b"[{")
+}
+
+/// 识别下载内容的实际格式并返回应使用的扩展名
+///
+/// 优先使用文件头判断，其次使用 Content-Type；HTML / JSON 响应会被拒绝。
+/// 识别出的格式与期望类型不符时同样返回错误。
+pub fn detect_extension(kind: MediaKind, content_type: Option<&str>, bytes: &[u8]) -> Result<&'static str, String> {
+    if bytes.is_empty() {
+        return Err("响应内容为空".to_string());
+    }
+
+    if looks_like_markup(content_type, bytes) {
+        return Err(format!(
+            "服务器返回了 HTML/JSON 而不是文件 (Content-Type: {})",
+            content_type.unwrap_or("unknown")
+        ));
+    }
+
+    if kind == MediaKind::Text {
+        return Ok(kind.default_extension());
+    }
+
+    let detected = sniff_magic(bytes).or_else(|| content_type.and_then(extension_from_content_type));
+    match detected {
+        Some(ext) if kind.extensions().contains(&ext) => Ok(ext),
+        Some(ext) => Err(format!("文件类型不符: 期望 {:?}，实际为 {}", kind, ext)),
+        None => {
+            tracing::warn!(
+                "无法识别文件格式 (Content-Type: {})，使用默认扩展名 {}",
+                content_type.unwrap_or("unknown"),
+                kind.default_extension()
+            );
+            Ok(kind.default_extension())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D];
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+    /// MPEG-1 Layer III 帧头，无 ID3 标签
+    const MP3_FRAME: &[u8] = &[0xFF, 0xFB, 0x90, 0x64, 0x00, 0x00];
+    const MP4: &[u8] = &[0, 0, 0, 0x20, b'f', b't', b'y', b'p', b'i', b's', b'o', b'm'];
+
+    #[test]
+    fn magic_bytes_win_over_content_type() {
+        assert_eq!(detect_extension(MediaKind::Image, Some("image/jpeg"), PNG), Ok("png"));
+        assert_eq!(detect_extension(MediaKind::Image, Some("application/octet-stream"), JPEG), Ok("jpg"));
+    }
+
+    #[test]
+    fn mp3_without_id3_tag() {
+        assert_eq!(sniff_magic(MP3_FRAME), Some("mp3"));
+        assert_eq!(detect_extension(MediaKind::Audio, None, MP3_FRAME), Ok("mp3"));
+        assert_eq!(sniff_magic(b"ID3\x04\x00"), Some("mp3"));
+        // JPEG 的 0xFF 0xD8 不是帧同步字
+        assert_eq!(sniff_magic(JPEG), Some("jpg"));
+    }
+
+    #[test]
+    fn html_error_page_is_rejected() {
+        let page = b"\r\n  <!DOCTYPE html><html><body>404 Not Found</body></html>";
+        assert!(detect_extension(MediaKind::Audio, Some("application/octet-stream"), page).is_err());
+        assert!(detect_extension(MediaKind::Image, None, b"<HTML><head></head></HTML>").is_err());
+        assert!(detect_extension(MediaKind::Text, Some("text/html; charset=utf-8"), b"&title=x").is_err());
+    }
+
+    #[test]
+    fn json_error_body_is_rejected() {
+        let body = br#"{"error":"chart not found"}"#;
+        assert!(detect_extension(MediaKind::Image, None, body).is_err());
+        assert!(detect_extension(MediaKind::Text, None, b"\xEF\xBB\xBF [{\"a\":1}]").is_err());
+        assert!(detect_extension(MediaKind::Video, Some("application/problem+json"), MP4).is_err());
+    }
+
+    #[test]
+    fn kind_mismatch_is_rejected() {
+        let err = detect_extension(MediaKind::Audio, Some("audio/mpeg"), PNG).unwrap_err();
+        assert!(err.contains("png"), "{}", err);
+        assert!(detect_extension(MediaKind::Image, None, MP4).is_err());
+        assert!(detect_extension(MediaKind::Video, Some("image/png"), b"unknown bytes").is_err());
+    }
+
+    #[test]
+    fn empty_body_is_rejected() {
+        assert!(detect_extension(MediaKind::Image, Some("image/png"), b"").is_err());
+        assert!(detect_extension(MediaKind::Text, None, b"").is_err());
+    }
+
+    #[test]
+    fn content_type_used_when_magic_is_unknown() {
+        assert_eq!(detect_extension(MediaKind::Audio, Some("Audio/OGG; codecs=vorbis"), b"????"), Ok("ogg"));
+        assert_eq!(extension_from_content_type("image/x-ms-bmp"), Some("bmp"));
+        assert_eq!(extension_from_content_type("application/octet-stream"), None);
+    }
+
+    #[test]
+    fn unknown_format_falls_back_to_default_extension() {
+        assert_eq!(detect_extension(MediaKind::Audio, None, b"????"), Ok("mp3"));
+        assert_eq!(detect_extension(MediaKind::Video, Some("application/octet-stream"), b"????"), Ok("mp4"));
+        assert_eq!(detect_extension(MediaKind::Text, Some("text/plain"), b"&title=Test\n&wholebpm=120"), Ok("txt"));
+    }
+}
@@ -0,0 +1,10 @@
+// 模块声明
+pub mod disk_cache;
+pub mod filename;
+pub mod json_edit;
+pub mod media;
+pub mod unity_log;
+
+// 重新导出常用工具函数
+pub use filename::{resolve_within, sanitize_file_name};
+pub use media::{detect_extension, MediaKind};
@@ -0,0 +1,255 @@
+use serde::Serialize;
+
+/// 每个异常最多保留的堆栈行数
+const MAX_STACK_LINES: usize = 40;
+
+/// Unity 崩溃报告中原生堆栈的起止标记
+const CRASH_STACK_BEGIN: &str = "OUTPUTTING STACK TRACE";
+const CRASH_STACK_END: &str = "END OF STACKTRACE";
+
+/// 从日志中提取的异常
+#[derive(Debug, Clone, Serialize)]
+pub struct LogException {
+    /// 来源日志文件
+    pub source: String,
+    /// 异常所在行号（从 1 开始，首次出现的位置）
+    pub line: usize,
+    pub message: String,
+    pub stack: Vec<String>,
+    /// 相同异常（消息和堆栈都相同）出现的次数
+    pub count: usize,
+}
+
+/// 是否为异常或崩溃的起始行
+///
+/// 匹配 `NullReferenceException: ...`、`System.IO.IOException: ...`、Unity 崩溃报告中的 `Crash!!!`
+/// 以及 `[Exception]`/`[Error]` 前缀的日志行。
+fn is_exception_start(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("Crash!!!") || trimmed.starts_with("[Exception]") || trimmed.starts_with("[Error]") {
+        return true;
+    }
+    // 取冒号前的部分作为类型名：只能由标识符字符和点组成
+    let Some((name, _)) = trimmed.split_once(':') else {
+        return false;
+    };
+    let is_type_name = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '`');
+    is_type_name && (name.ends_with("Exception") || name.ends_with("Error"))
+}
+
+/// 是否为堆栈帧
+///
+/// 支持 Mono 格式（`  at Foo.Bar () [0x00000] in <...>:0`）、Unity 格式（`Foo:Bar() (at Assets/...)`、
+/// `UnityEngine.Debug:LogException(Exception)`）以及原生崩溃堆栈（`0x00007FF...`）。
+fn is_stack_frame(line: &str) -> bool {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+    trimmed.starts_with("at ")
+        || trimmed.starts_with("--- End of")
+        || trimmed.starts_with("0x")
+        || trimmed.contains("(at ")
+        || trimmed.starts_with("Rethrow as ")
+        || is_unity_frame(trimmed)
+}
+
+/// Unity 格式的堆栈帧：`Namespace.Type:Method(Args)`
+fn is_unity_frame(line: &str) -> bool {
+    let Some((type_name, rest)) = line.split_once(':') else {
+        return false;
+    };
+    !type_name.is_empty()
+        && !type_name.contains(' ')
+        && rest.contains('(')
+        && rest.trim_end().ends_with(')')
+}
+
+/// 读取 Crash!!! 报告的原生堆栈，返回报告之后的行号
+///
+/// 报告先输出符号路径和模块列表，堆栈位于 `OUTPUTTING STACK TRACE` 和 `END OF STACKTRACE` 两个标记之间；
+/// 日志被截断时在下一个异常处结束。
+fn read_crash_stack(lines: &[&str], mut i: usize, stack: &mut Vec<String>) -> usize {
+    let mut in_stack = false;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.contains(CRASH_STACK_END) {
+            return i + 1;
+        }
+        if is_exception_start(line) {
+            return i;
+        }
+        if line.contains(CRASH_STACK_BEGIN) {
+            in_stack = true;
+        } else if in_stack && !line.is_empty() && stack.len() < MAX_STACK_LINES {
+            stack.push(line.to_string());
+        }
+        i += 1;
+    }
+    i
+}
+
+/// 提取日志中的异常和堆栈，相同的异常合并计数
+pub fn extract_exceptions(source: &str, text: &str) -> Vec<LogException> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut exceptions: Vec<LogException> = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        if !is_exception_start(lines[i]) {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let message = lines[i].trim().to_string();
+        let mut stack = Vec::new();
+        i += 1;
+
+        if message.starts_with("Crash!!!") {
+            i = read_crash_stack(&lines, i, &mut stack);
+        } else {
+            while i < lines.len() && stack.len() < MAX_STACK_LINES && is_stack_frame(lines[i]) {
+                stack.push(lines[i].trim().to_string());
+                i += 1;
+            }
+        }
+
+        match exceptions.iter_mut().find(|e| e.message == message && e.stack == stack) {
+            Some(existing) => existing.count += 1,
+            None => exceptions.push(LogException {
+                source: source.to_string(),
+                line: start + 1,
+                message,
+                stack,
+                count: 1,
+            }),
+        }
+    }
+
+    exceptions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONO_EXCEPTION: &str = "\
+Loading chart: Songs/Default/Sample
+NullReferenceException: Object reference not set to an instance of an object
+  at MajdataPlay.Game.NoteLoader.LoadNotes (MajdataPlay.Types.SimaiChart chart) [0x0002a] in <8f3c2d1e9b7a4c5d>:0 
+  at MajdataPlay.Game.GamePlayManager.Start () [0x00110] in <8f3c2d1e9b7a4c5d>:0 
+UnityEngine.Debug:LogException(Exception)
+MajdataPlay.Game.GamePlayManager:Start() (at Assets/Scripts/Game/GamePlayManager.cs:132)
+
+(Filename: <8f3c2d1e9b7a4c5d> Line: 0)
+
+Unloading 3 unused Assets to reduce memory usage.
+";
+
+    const UNITY_EXCEPTION: &str = "\
+[Exception] InvalidOperationException: Sequence contains no elements
+System.Linq.Enumerable.First[TSource] (System.Collections.Generic.IEnumerable`1[T] source) (at <1a2b3c4d5e6f>:0)
+MajdataPlay.IO.InputManager:Update() (at Assets/Scripts/IO/InputManager.cs:58)
+UnityEngine.Debug:LogException(Exception)
+
+";
+
+    const CRASH_REPORT: &str = "\
+Crash!!!
+SymInit: Symbol-SearchPath: '.;C:\\Games\\MajdataPlay;C:\\Windows;C:\\Windows\\system32;', symOptions: 534, UserOptions: 0
+OS-Version: 10.0.0
+C:\\Games\\MajdataPlay\\MajdataPlay.exe:(0x00007FF6A1B20000), size: 659968 (result: 0), SymType: '-exported-', PDB: ''
+C:\\Windows\\System32\\KERNEL32.DLL:(0x00007FFB6C3E0000), size: 806912 (result: 0), SymType: '-exported-', PDB: ''
+
+========== OUTPUTTING STACK TRACE ==================
+
+0x00007FFAF1A2B3C4 (UnityPlayer) UnityMain
+0x00007FFAF1A2B123 (UnityPlayer) UnityMain
+0x00007FFB6C3F7344 (KERNEL32) BaseThreadInitThunk
+
+========== END OF STACKTRACE ===========
+
+A crash has been intercepted by the crash handler. For call stack and other details, see the latest crash report generated in:
+";
+
+    #[test]
+    fn extracts_mono_exception_with_unity_frames() {
+        let exceptions = extract_exceptions("Player.log", MONO_EXCEPTION);
+        assert_eq!(exceptions.len(), 1);
+        let e = &exceptions[0];
+        assert_eq!(e.source, "Player.log");
+        assert_eq!(e.line, 2);
+        assert_eq!(e.message, "NullReferenceException: Object reference not set to an instance of an object");
+        assert_eq!(e.stack.len(), 4);
+        assert!(e.stack[0].starts_with("at MajdataPlay.Game.NoteLoader.LoadNotes"));
+        assert_eq!(e.stack[2], "UnityEngine.Debug:LogException(Exception)");
+        assert!(e.stack[3].ends_with("(at Assets/Scripts/Game/GamePlayManager.cs:132)"));
+        assert_eq!(e.count, 1);
+    }
+
+    #[test]
+    fn extracts_prefixed_exception() {
+        let exceptions = extract_exceptions("Player.log", UNITY_EXCEPTION);
+        assert_eq!(exceptions.len(), 1);
+        assert_eq!(exceptions[0].message, "[Exception] InvalidOperationException: Sequence contains no elements");
+        assert_eq!(exceptions[0].stack.len(), 3);
+    }
+
+    #[test]
+    fn extracts_error_prefixed_line() {
+        let text = "[Error] Failed to load skin: Skins/Custom\nLoading scene\n";
+        let exceptions = extract_exceptions("Player.log", text);
+        assert_eq!(exceptions.len(), 1);
+        assert_eq!(exceptions[0].message, "[Error] Failed to load skin: Skins/Custom");
+        assert!(exceptions[0].stack.is_empty());
+    }
+
+    #[test]
+    fn extracts_native_crash_stack() {
+        let text = format!("{}{}", MONO_EXCEPTION, CRASH_REPORT);
+        let exceptions = extract_exceptions("Player.log", &text);
+        assert_eq!(exceptions.len(), 2);
+        let crash = &exceptions[1];
+        assert_eq!(crash.message, "Crash!!!");
+        assert_eq!(crash.stack, vec![
+            "0x00007FFAF1A2B3C4 (UnityPlayer) UnityMain",
+            "0x00007FFAF1A2B123 (UnityPlayer) UnityMain",
+            "0x00007FFB6C3F7344 (KERNEL32) BaseThreadInitThunk",
+        ]);
+    }
+
+    #[test]
+    fn truncated_crash_report_stops_at_next_exception() {
+        let text = format!("Crash!!!\nOS-Version: 10.0.0\n{}", MONO_EXCEPTION);
+        let exceptions = extract_exceptions("Player.log", &text);
+        assert_eq!(exceptions.len(), 2);
+        assert!(exceptions[0].stack.is_empty());
+        assert!(exceptions[1].message.starts_with("NullReferenceException"));
+    }
+
+    #[test]
+    fn merges_identical_exceptions() {
+        let text = format!("{}{}{}", MONO_EXCEPTION, UNITY_EXCEPTION, MONO_EXCEPTION);
+        let exceptions = extract_exceptions("Player.log", &text);
+        assert_eq!(exceptions.len(), 2);
+        assert_eq!(exceptions[0].count, 2);
+        assert_eq!(exceptions[0].line, 2);
+        assert_eq!(exceptions[1].count, 1);
+    }
+
+    #[test]
+    fn same_message_with_different_stack_is_kept() {
+        let other = MONO_EXCEPTION.replace("LoadNotes", "LoadBgm");
+        let text = format!("{}{}", MONO_EXCEPTION, other);
+        let exceptions = extract_exceptions("Player.log", &text);
+        assert_eq!(exceptions.len(), 2);
+    }
+
+    #[test]
+    fn ignores_regular_log_lines() {
+        let text = "Initialize engine version: 2022.3.20f1\nDirect3D:\n    Version:  Direct3D 11.0 [level 11.1]\n";
+        assert!(extract_exceptions("Player.log", text).is_empty());
+    }
+}
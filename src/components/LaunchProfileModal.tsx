@@ -0,0 +1,131 @@
+import { useState, useEffect } from 'react';
+import { Modal, Stack, TextInput, Textarea, Group, Button } from '@mantine/core';
+import { notifications } from '@mantine/notifications';
+import { invoke } from '@tauri-apps/api/core';
+
+export interface LaunchProfile {
+  id: string;
+  label: string;
+  description: string;
+  args: string[];
+  env: Record<string, string>;
+  working_dir?: string | null;
+  pre_launch?: string | null;
+  post_exit?: string | null;
+}
+
+interface LaunchProfileModalProps {
+  opened: boolean;
+  profile: LaunchProfile | null;  // 为空时新建
+  onClose: () => void;
+  onSaved: (profiles: LaunchProfile[], savedId: string) => void;
+}
+
+// 按空白拆分参数，支持双引号
+const splitArgs = (text: string): string[] =>
+  (text.match(/"[^"]*"|\S+/g) ?? []).map(arg => arg.replace(/^"(.*)"$/, '$1'));
+
+const joinArgs = (args: string[]): string =>
+  args.map(arg => (/\s/.test(arg) ? `"${arg}"` : arg)).join(' ');
+
+export function LaunchProfileModal({ opened, profile, onClose, onSaved }: LaunchProfileModalProps) {
+  const [id, setId] = useState('');
+  const [label, setLabel] = useState('');
+  const [description, setDescription] = useState('');
+  const [args, setArgs] = useState('');
+  const [env, setEnv] = useState('');
+  const [workingDir, setWorkingDir] = useState('');
+  const [preLaunch, setPreLaunch] = useState('');
+  const [postExit, setPostExit] = useState('');
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    if (!opened) return;
+    setId(profile?.id ?? '');
+    setLabel(profile?.label ?? '');
+    setDescription(profile?.description ?? '');
+    setArgs(joinArgs(profile?.args ?? []));
+    setEnv(Object.entries(profile?.env ?? {}).map(([k, v]) => `${k}=${v}`).join('\n'));
+    setWorkingDir(profile?.working_dir ?? '');
+    setPreLaunch(profile?.pre_launch ?? '');
+    setPostExit(profile?.post_exit ?? '');
+  }, [opened, profile]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    try {
+      const saved: LaunchProfile = {
+        id: id.trim(),
+        label: label.trim(),
+        description,
+        args: splitArgs(args),
+        env: Object.fromEntries(
+          env
+            .split('\n')
+            .map(line => line.trim())
+            .filter(line => line.includes('='))
+            .map(line => [line.slice(0, line.indexOf('=')).trim(), line.slice(line.indexOf('=') + 1)])
+        ),
+        working_dir: workingDir.trim() || null,
+        pre_launch: preLaunch.trim() || null,
+        post_exit: postExit.trim() || null,
+      };
+      const profiles = await invoke<LaunchProfile[]>('save_launch_profile', { profile: saved });
+      onSaved(profiles, saved.id);
+      onClose();
+    } catch (error) {
+      notifications.show({
+        title: '错误',
+        message: '保存启动配置失败: ' + String(error),
+        color: 'red',
+      });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Modal opened={opened} onClose={onClose} title={profile ? '编辑启动配置' : '新建启动配置'} size="lg">
+      <Stack gap="sm">
+        <TextInput label="ID" value={id} onChange={(e) => setId(e.currentTarget.value)} disabled={!!profile} required />
+        <TextInput label="名称" value={label} onChange={(e) => setLabel(e.currentTarget.value)} required />
+        <TextInput label="说明" value={description} onChange={(e) => setDescription(e.currentTarget.value)} />
+        <TextInput
+          label="启动参数"
+          placeholder="-screen-fullscreen 0 -screen-width 1920"
+          value={args}
+          onChange={(e) => setArgs(e.currentTarget.value)}
+        />
+        <Textarea
+          label="环境变量"
+          description="每行一个，格式为 KEY=VALUE"
+          value={env}
+          onChange={(e) => setEnv(e.currentTarget.value)}
+          autosize
+          minRows={2}
+        />
+        <TextInput
+          label="工作目录"
+          description="留空表示游戏目录，相对路径基于游戏目录"
+          value={workingDir}
+          onChange={(e) => setWorkingDir(e.currentTarget.value)}
+        />
+        <TextInput
+          label="启动前命令"
+          description="失败时取消启动"
+          value={preLaunch}
+          onChange={(e) => setPreLaunch(e.currentTarget.value)}
+        />
+        <TextInput
+          label="退出后命令"
+          value={postExit}
+          onChange={(e) => setPostExit(e.currentTarget.value)}
+        />
+        <Group justify="flex-end" mt="md">
+          <Button variant="default" onClick={onClose}>取消</Button>
+          <Button onClick={handleSave} loading={saving} disabled={!id.trim() || !label.trim()}>保存</Button>
+        </Group>
+      </Stack>
+    </Modal>
+  );
+}
@@ -6,6 +6,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { convertFileSrc } from '@tauri-apps/api/core';
 import { ask } from '@tauri-apps/plugin-dialog';
 import { usePathContext } from '../contexts';
+import { formatError } from '../utils/error';
 
 interface ChartInfo {
   name: string;
@@ -73,19 +74,15 @@ export function LocalCharts({ onRefresh, refreshTrigger }: LocalChartsProps) {
 
     try {
       setLoading(true);
-      const maichartsPath = `${defaultGameFolderPath}\\MaiCharts`;
-      
+
       // 加载所有分类
-      const cats = await invoke<string[]>('list_chart_categories', { maichartsDir: maichartsPath });
+      const cats = await invoke<string[]>('list_chart_categories');
       setCategories(cats);
 
       // 加载每个分类的谱面
       const chartData: Record<string, ChartInfo[]> = {};
       for (const category of cats) {
-        const charts = await invoke<ChartInfo[]>('list_charts_in_category', { 
-          maichartsDir: maichartsPath,
-          category 
-        });
+        const charts = await invoke<ChartInfo[]>('list_charts_in_category', { category });
         chartData[category] = charts;
       }
       setChartsByCategory(chartData);
@@ -111,9 +108,7 @@ export function LocalCharts({ onRefresh, refreshTrigger }: LocalChartsProps) {
     if (!confirmed) return;
 
     try {
-      const maichartsPath = `${defaultGameFolderPath}\\MaiCharts`;
       await invoke('delete_chart', {
-        maichartsDir: maichartsPath,
         category: chart.category,
         chartName: chart.name,
       });
@@ -133,7 +128,7 @@ export function LocalCharts({ onRefresh, refreshTrigger }: LocalChartsProps) {
       console.error('删除谱面失败:', error);
       notifications.show({
         title: '错误',
-        message: '删除谱面失败: ' + String(error),
+        message: '删除谱面失败: ' + formatError(error),
         color: 'red',
       });
     }
@@ -154,19 +149,15 @@ export function LocalCharts({ onRefresh, refreshTrigger }: LocalChartsProps) {
     }
 
     try {
-      const maichartsPath = `${defaultGameFolderPath}\\MaiCharts`;
-
       // 如果是新分类，先创建
       if (newCategoryName && !categories.includes(finalCategory)) {
         await invoke('create_chart_category', {
-          maichartsDir: maichartsPath,
           category: finalCategory,
         });
         setCategories([...categories, finalCategory]);
       }
 
       await invoke('move_chart', {
-        maichartsDir: maichartsPath,
         fromCategory: chartToMove.category,
         toCategory: finalCategory,
         chartName: chartToMove.name,
@@ -197,7 +188,7 @@ export function LocalCharts({ onRefresh, refreshTrigger }: LocalChartsProps) {
       console.error('移动谱面失败:', error);
       notifications.show({
         title: '错误',
-        message: '移动谱面失败: ' + String(error),
+        message: '移动谱面失败: ' + formatError(error),
         color: 'red',
       });
     }
@@ -1,15 +1,39 @@
 import { useState, useEffect } from 'react';
-import { Container, Card, Group, Text, Button, Stack, LoadingOverlay, Grid } from '@mantine/core';
+import { Container, Card, Group, Text, Button, Badge, Stack, LoadingOverlay, Grid, Image, Modal, Select, MultiSelect, TextInput } from '@mantine/core';
 import { notifications } from '@mantine/notifications';
-import { IconTrash, IconRefresh } from '@tabler/icons-react';
-import { invoke } from '@tauri-apps/api/core';
+import { IconTrash, IconRefresh, IconCloudDownload, IconStack2, IconCheck } from '@tabler/icons-react';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 import { ask } from '@tauri-apps/plugin-dialog';
 import { usePathContext } from '../contexts';
+import { formatError } from '../utils/error';
 
 interface SkinInfo {
   name: string;
+  author?: string | null;
+  version?: string | null;
+  description?: string | null;
+  preview?: string | null;  // 预览图本地路径
+  size: number;
+  file_count: number;
 }
 
+interface SkinUpdate {
+  name: string;
+  latest: {
+    name: string;
+    download_url: string;
+    size: number;
+    sha?: string | null;
+    source: string;
+  };
+}
+
+const formatFileSize = (bytes: number): string => {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(2)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(2)} MB`;
+};
+
 interface LocalSkinsProps {
   onRefresh?: () => void;
   refreshTrigger?: number;
@@ -19,6 +43,16 @@ export function LocalSkins({ onRefresh, refreshTrigger }: LocalSkinsProps) {
   const { defaultGameFolderPath } = usePathContext();
   const [skins, setSkins] = useState<SkinInfo[]>([]);
   const [loading, setLoading] = useState(true);
+  const [updates, setUpdates] = useState<Record<string, SkinUpdate>>({});
+  const [checkingUpdates, setCheckingUpdates] = useState(false);
+  const [upgrading, setUpgrading] = useState<string | null>(null);
+  const [composeOpen, setComposeOpen] = useState(false);
+  const [composeBase, setComposeBase] = useState<string | null>(null);
+  const [composeOverlays, setComposeOverlays] = useState<string[]>([]);
+  const [composeName, setComposeName] = useState('');
+  const [composing, setComposing] = useState(false);
+  const [activeSkin, setActiveSkin] = useState<string | null>(null);
+  const [activating, setActivating] = useState<string | null>(null);
 
   useEffect(() => {
     loadSkins();
@@ -38,10 +72,15 @@ export function LocalSkins({ onRefresh, refreshTrigger }: LocalSkinsProps) {
 
     try {
       setLoading(true);
-      const skinsPath = `${defaultGameFolderPath}\\Skins`;
-      
-      const skinList = await invoke<SkinInfo[]>('list_skins', { skinsDir: skinsPath });
+      const skinList = await invoke<SkinInfo[]>('list_skins');
       setSkins(skinList);
+      // 游戏尚未生成设置文件时没有当前皮肤
+      invoke<string>('get_active_skin')
+        .then(setActiveSkin)
+        .catch((error) => {
+          console.warn('读取当前皮肤失败:', error);
+          setActiveSkin(null);
+        });
     } catch (error) {
       console.error('加载皮肤失败:', error);
       notifications.show({
@@ -64,9 +103,7 @@ export function LocalSkins({ onRefresh, refreshTrigger }: LocalSkinsProps) {
     if (!confirmed) return;
 
     try {
-      const skinsPath = `${defaultGameFolderPath}\\Skins`;
       await invoke('delete_skin', {
-        skinsDir: skinsPath,
         skinName: skin.name,
       });
 
@@ -82,12 +119,121 @@ export function LocalSkins({ onRefresh, refreshTrigger }: LocalSkinsProps) {
       console.error('删除皮肤失败:', error);
       notifications.show({
         title: '错误',
-        message: '删除皮肤失败: ' + String(error),
+        message: '删除皮肤失败: ' + formatError(error),
         color: 'red',
       });
     }
   };
 
+  const handleActivateSkin = async (skin: SkinInfo) => {
+    setActivating(skin.name);
+    try {
+      await invoke('set_active_skin', { skinName: skin.name });
+      setActiveSkin(skin.name);
+      notifications.show({
+        title: '成功',
+        message: `游戏皮肤已切换为 "${skin.name}"`,
+        color: 'green',
+      });
+    } catch (error) {
+      console.error('切换皮肤失败:', error);
+      notifications.show({
+        title: '错误',
+        message: '切换皮肤失败: ' + formatError(error),
+        color: 'red',
+      });
+    } finally {
+      setActivating(null);
+    }
+  };
+
+  const checkUpdates = async () => {
+    if (!defaultGameFolderPath) return;
+
+    setCheckingUpdates(true);
+    try {
+      const list = await invoke<SkinUpdate[]>('check_skin_updates');
+      setUpdates(Object.fromEntries(list.map(u => [u.name, u])));
+      notifications.show({
+        title: '检查更新',
+        message: list.length > 0 ? `${list.length} 个皮肤有更新` : '所有皮肤均为最新',
+        color: list.length > 0 ? 'blue' : 'green',
+      });
+    } catch (error) {
+      console.error('检查皮肤更新失败:', error);
+      notifications.show({
+        title: '错误',
+        message: '检查皮肤更新失败: ' + String(error),
+        color: 'red',
+      });
+    } finally {
+      setCheckingUpdates(false);
+    }
+  };
+
+  const handleUpgradeSkin = async (update: SkinUpdate) => {
+    if (!defaultGameFolderPath) return;
+
+    setUpgrading(update.name);
+    try {
+      await invoke('download_skin_zip', {
+        skin: update.latest,
+      });
+      notifications.show({
+        title: '成功',
+        message: `皮肤 "${update.name}" 已更新，旧版本已备份到 SkinBackups`,
+        color: 'green',
+      });
+      setUpdates(prev => {
+        const next = { ...prev };
+        delete next[update.name];
+        return next;
+      });
+      loadSkins();
+    } catch (error) {
+      console.error('更新皮肤失败:', error);
+      notifications.show({
+        title: '错误',
+        message: '更新皮肤失败: ' + formatError(error),
+        color: 'red',
+      });
+    } finally {
+      setUpgrading(null);
+    }
+  };
+
+  const handleComposeSkin = async () => {
+    if (!defaultGameFolderPath || !composeBase || !composeName.trim()) return;
+
+    setComposing(true);
+    try {
+      const result = await invoke<{ name: string; files: Record<string, string>; validation: { valid: boolean; missing: string[] } }>('compose_skin', {
+        base: composeBase,
+        overlays: composeOverlays,
+        outputName: composeName.trim(),
+      });
+      notifications.show({
+        title: '成功',
+        message: `已生成皮肤 "${result.name}"（${Object.keys(result.files).length} 个文件）`
+          + (result.validation.valid ? '' : `，缺失: ${result.validation.missing.join(', ')}`),
+        color: result.validation.valid ? 'green' : 'yellow',
+      });
+      setComposeOpen(false);
+      setComposeOverlays([]);
+      setComposeName('');
+      loadSkins();
+      onRefresh?.();
+    } catch (error) {
+      console.error('组合皮肤失败:', error);
+      notifications.show({
+        title: '错误',
+        message: '组合皮肤失败: ' + formatError(error),
+        color: 'red',
+      });
+    } finally {
+      setComposing(false);
+    }
+  };
 
   if (loading) {
     return (
@@ -110,19 +256,69 @@ export function LocalSkins({ onRefresh, refreshTrigger }: LocalSkinsProps) {
       <Stack gap="md">
         <Group justify="space-between">
           <Text size="lg" fw={600}>本地皮肤 ({skins.length})</Text>
-          <Button onClick={loadSkins} variant="light" leftSection={<IconRefresh size={16} />}>
-            刷新
-          </Button>
+          <Group gap="xs">
+            <Button onClick={() => setComposeOpen(true)} variant="light" leftSection={<IconStack2 size={16} />}>
+              组合皮肤
+            </Button>
+            <Button onClick={checkUpdates} variant="light" loading={checkingUpdates} leftSection={<IconCloudDownload size={16} />}>
+              检查更新
+            </Button>
+            <Button onClick={loadSkins} variant="light" leftSection={<IconRefresh size={16} />}>
+              刷新
+            </Button>
+          </Group>
         </Group>
 
         <Grid gutter="sm">
           {skins.map(skin => (
             <Grid.Col key={skin.name} span={{ base: 12, sm: 6, md: 4, lg: 3 }}>
               <Card shadow="sm" padding="md" radius="md" withBorder>
-                <Stack gap="xs">
-                  <Text fw={500} size="sm" lineClamp={1}>
-                    {skin.name}
+                {skin.preview && (
+                  <Card.Section>
+                    <Image src={convertFileSrc(skin.preview)} height={120} alt={skin.name} fit="cover" />
+                  </Card.Section>
+                )}
+                <Stack gap="xs" mt={skin.preview ? 'sm' : 0}>
+                  <Group gap="xs" wrap="nowrap">
+                    <Text fw={500} size="sm" lineClamp={1} style={{ flex: 1 }}>
+                      {skin.name}
+                      {skin.version && <Text span size="xs" c="dimmed"> v{skin.version}</Text>}
+                    </Text>
+                    {activeSkin === skin.name && <Badge size="xs" color="green">使用中</Badge>}
+                  </Group>
+                  {skin.author && (
+                    <Text size="xs" c="dimmed" lineClamp={1}>作者: {skin.author}</Text>
+                  )}
+                  {skin.description && (
+                    <Text size="xs" lineClamp={2}>{skin.description}</Text>
+                  )}
+                  <Text size="xs" c="dimmed">
+                    {formatFileSize(skin.size)} · {skin.file_count} 个文件
                   </Text>
+                  {activeSkin !== skin.name && (
+                    <Button
+                      fullWidth
+                      variant="light"
+                      size="xs"
+                      leftSection={<IconCheck size={14} />}
+                      loading={activating === skin.name}
+                      onClick={() => handleActivateSkin(skin)}
+                    >
+                      设为游戏皮肤
+                    </Button>
+                  )}
+                  {updates[skin.name] && (
+                    <Button
+                      fullWidth
+                      variant="light"
+                      size="xs"
+                      leftSection={<IconCloudDownload size={14} />}
+                      loading={upgrading === skin.name}
+                      onClick={() => handleUpgradeSkin(updates[skin.name])}
+                    >
+                      更新
+                    </Button>
+                  )}
                   <Button
                     fullWidth
                     color="red"
@@ -139,6 +335,36 @@ export function LocalSkins({ onRefresh, refreshTrigger }: LocalSkinsProps) {
           ))}
         </Grid>
       </Stack>
+
+      <Modal opened={composeOpen} onClose={() => setComposeOpen(false)} title="组合皮肤">
+        <Stack gap="md">
+          <Select
+            label="基础皮肤"
+            data={skins.map(s => s.name)}
+            value={composeBase}
+            onChange={setComposeBase}
+            searchable
+          />
+          <MultiSelect
+            label="叠加皮肤（按选择顺序，后选的覆盖先选的）"
+            data={skins.map(s => s.name).filter(name => name !== composeBase)}
+            value={composeOverlays}
+            onChange={setComposeOverlays}
+            searchable
+          />
+          <TextInput
+            label="新皮肤名称"
+            value={composeName}
+            onChange={(e) => setComposeName(e.target.value)}
+          />
+          <Group justify="flex-end">
+            <Button variant="default" onClick={() => setComposeOpen(false)}>取消</Button>
+            <Button onClick={handleComposeSkin} loading={composing} disabled={!composeBase || !composeName.trim()}>
+              生成
+            </Button>
+          </Group>
+        </Stack>
+      </Modal>
     </Container>
   );
 }
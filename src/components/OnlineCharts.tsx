@@ -1,10 +1,11 @@
-import { useState, useEffect } from 'react';
-import { Container, TextInput, Select, Card, Group, Text, Button, Badge, Stack, Grid, Modal, LoadingOverlay, Pagination, Image, Divider, Loader, Checkbox, ScrollArea, Progress } from '@mantine/core';
+import { useState, useEffect, useRef } from 'react';
+import { Container, TextInput, Select, Card, Group, Text, Button, Badge, Stack, Grid, Modal, LoadingOverlay, Pagination, Image, Divider, Loader, Checkbox, ScrollArea, Progress, ActionIcon } from '@mantine/core';
 import { notifications } from '@mantine/notifications';
-import { IconDownload, IconSearch, IconPlus, IconCheckbox, IconSquare, IconRefresh } from '@tabler/icons-react';
-import { invoke } from '@tauri-apps/api/core';
+import { IconDownload, IconSearch, IconPlus, IconCheckbox, IconSquare, IconRefresh, IconPlayerPlay, IconPlayerStop } from '@tabler/icons-react';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { usePathContext } from '../contexts';
+import { formatError } from '../utils/error';
 
 const API_ROOT = 'https://majdata.net/api3/api';
 
@@ -17,6 +18,11 @@ interface ChartSummary {
   levels: (string | null)[];  // API 返回数组，可能包含 null、空字符串或 "14", "13+" 等
 }
 
+interface ChartListPage {
+  charts: ChartSummary[];
+  has_more: boolean;
+}
+
 interface OnlineChartsProps {
   onRefresh?: () => void;
 }
@@ -25,8 +31,9 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
   const { defaultGameFolderPath } = usePathContext();
   const [search, setSearch] = useState('');
   const [debouncedSearch, setDebouncedSearch] = useState('');
-  const [sortType, setSortType] = useState(0);
+  const [sortType, setSortType] = useState('date');
   const [charts, setCharts] = useState<ChartSummary[]>([]);
+  const [thumbnails, setThumbnails] = useState<Record<string, string>>({});
   const [loading, setLoading] = useState(false);
   const [page, setPage] = useState(0);
   const [totalPages, setTotalPages] = useState(1);
@@ -39,20 +46,27 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
   const [selectedChartIds, setSelectedChartIds] = useState<Set<string>>(new Set());
   const [isBatchMode, setIsBatchMode] = useState(false);
   const [downloadProgress, setDownloadProgress] = useState({ current: 0, total: 0 });
-
-  const ITEMS_PER_PAGE = 30;
+  const [previewingId, setPreviewingId] = useState<string | null>(null);
+  const [previewLoadingId, setPreviewLoadingId] = useState<string | null>(null);
+  const audioRef = useRef<HTMLAudioElement | null>(null);
+  const previewRequestRef = useRef<string | null>(null);
 
   const sortOptions = [
-    { value: '0', label: '上传日期' },
-    { value: '1', label: '点赞数' },
-    { value: '2', label: '评论数' },
-    { value: '3', label: '播放数' },
+    { value: 'date', label: '上传日期' },
+    { value: 'likes', label: '点赞数' },
+    { value: 'comments', label: '评论数' },
+    { value: 'plays', label: '播放数' },
   ];
 
   useEffect(() => {
     loadCategories();
   }, [defaultGameFolderPath]);
 
+  // 离开页面时停止试听
+  useEffect(() => () => {
+    audioRef.current?.pause();
+  }, []);
+
   // 监听下载进度事件
   useEffect(() => {
     let unlisten: (() => void) | undefined;
@@ -90,8 +104,7 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
     if (!defaultGameFolderPath) return;
 
     try {
-      const maichartsPath = `${defaultGameFolderPath}\\MaiCharts`;
-      const cats = await invoke<string[]>('list_chart_categories', { maichartsDir: maichartsPath });
+      const cats = await invoke<string[]>('list_chart_categories');
       setCategories(cats);
       if (cats.length > 0) {
         setTargetCategory(cats[0]);
@@ -104,16 +117,25 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
   const searchCharts = async () => {
     setLoading(true);
     try {
-      const charts = await invoke<ChartSummary[]>('fetch_chart_list', {
-        search: debouncedSearch,
-        sortType,
-        page,
-        proxy: null,
+      const result = await invoke<ChartListPage>('fetch_chart_list', {
+        query: {
+          search: debouncedSearch,
+          sort: sortType,
+          page,
+        },
       });
       
+      const charts = result?.charts;
       if (Array.isArray(charts)) {
         setCharts(charts);
-        if (charts.length < ITEMS_PER_PAGE) {
+        // 后台预取本地缩略图，完成前先使用在线图片
+        invoke<Record<string, string>>('prefetch_chart_thumbnails', {
+          chartIds: charts.map(c => c.id),
+        })
+          .then(setThumbnails)
+          .catch((error) => console.error('预取缩略图失败:', error));
+        // 过滤在本地进行，结果可能少于一页，由后端根据过滤前的数量判断是否还有下一页
+        if (!result.has_more) {
           setTotalPages(page + 1);
         } else if (totalPages === page + 1) {
           setTotalPages(page + 2);
@@ -134,6 +156,47 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
     }
   };
 
+  const stopPreview = () => {
+    previewRequestRef.current = null;
+    audioRef.current?.pause();
+    audioRef.current = null;
+    setPreviewingId(null);
+  };
+
+  const togglePreview = async (chart: ChartSummary) => {
+    if (previewingId === chart.id) {
+      stopPreview();
+      return;
+    }
+    stopPreview();
+    previewRequestRef.current = chart.id;
+    setPreviewLoadingId(chart.id);
+    try {
+      const path = await invoke<string>('preview_chart_track', { chartId: chart.id });
+      // 加载期间用户已切换或停止试听
+      if (previewRequestRef.current !== chart.id) return;
+      const audio = new Audio(convertFileSrc(path));
+      audio.onended = () => {
+        if (audioRef.current === audio) {
+          audioRef.current = null;
+          setPreviewingId(null);
+        }
+      };
+      audioRef.current = audio;
+      await audio.play();
+      setPreviewingId(chart.id);
+    } catch (error) {
+      console.error('试听失败:', error);
+      notifications.show({
+        title: '错误',
+        message: '试听失败: ' + String(error),
+        color: 'red',
+      });
+    } finally {
+      setPreviewLoadingId(current => (current === chart.id ? null : current));
+    }
+  };
+
   const openDownloadModal = (chart: ChartSummary) => {
     setSelectedChart(chart);
     setTargetCategory(null);
@@ -204,14 +267,12 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
     }
   };
 
-  const downloadSingleChart = async (chart: ChartSummary, finalCategory: string, maichartsPath: string) => {
+  const downloadSingleChart = async (chart: ChartSummary, finalCategory: string) => {
     // 调用Rust端批量下载命令（单个谱面）
     await invoke('download_charts_batch', {
       chartIds: [chart.id],
       chartTitles: [chart.title],
-      maichartsDir: maichartsPath,
       category: finalCategory,
-      proxy: null,
     });
   };
 
@@ -231,12 +292,9 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
 
     setDownloading(true);
     try {
-      const maichartsPath = `${defaultGameFolderPath}\\MaiCharts`;
-
       // 如果是新分类，先创建
       if (newCategoryName && !categories.includes(finalCategory)) {
         await invoke('create_chart_category', {
-          maichartsDir: maichartsPath,
           category: finalCategory,
         });
         setCategories([...categories, finalCategory]);
@@ -251,9 +309,7 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
         await invoke('download_charts_batch', {
           chartIds: chartsToDownload.map(c => c.id),
           chartTitles: chartsToDownload.map(c => c.title),
-          maichartsDir: maichartsPath,
           category: finalCategory,
-          proxy: null,
         });
 
         notifications.show({
@@ -266,7 +322,7 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
         setIsBatchMode(false);
       } else {
         // 单个下载
-        await downloadSingleChart(selectedChart, finalCategory, maichartsPath);
+        await downloadSingleChart(selectedChart, finalCategory);
 
         notifications.show({
           title: '成功',
@@ -281,7 +337,7 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
       console.error('下载谱面失败:', error);
       notifications.show({
         title: '错误',
-        message: '下载谱面失败: ' + String(error),
+        message: '下载谱面失败: ' + formatError(error),
         color: 'red',
       });
     } finally {
@@ -304,8 +360,8 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
             />
             <Select
               data={sortOptions}
-              value={String(sortType)}
-              onChange={(val) => setSortType(Number(val))}
+              value={sortType}
+              onChange={(val) => setSortType(val ?? 'date')}
               style={{ width: 150 }}
             />
           </Group>
@@ -381,7 +437,7 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
                       )}
                       <Card.Section>
                         <Image
-                          src={`${API_ROOT}/maichart/${chart.id}/image`}
+                          src={thumbnails[chart.id] ? convertFileSrc(thumbnails[chart.id]) : `${API_ROOT}/maichart/${chart.id}/image`}
                           height={100}
                           alt={chart.title}
                           fallbackSrc="data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' width='400' height='300'%3E%3Crect width='400' height='300' fill='%23ddd'/%3E%3Ctext x='50%25' y='50%25' dominant-baseline='middle' text-anchor='middle' font-family='sans-serif' font-size='18' fill='%23999'%3ENo Image%3C/text%3E%3C/svg%3E"
@@ -409,15 +465,25 @@ export function OnlineCharts({ onRefresh }: OnlineChartsProps) {
                         </Group>
 
                         {!isBatchMode && (
-                          <Button
-                            fullWidth
-                            mt="xs"
-                            size="xs"
-                            leftSection={<IconDownload size={14} />}
-                            onClick={() => openDownloadModal(chart)}
-                          >
-                            下载
-                          </Button>
+                          <Group gap={4} mt="xs" wrap="nowrap">
+                            <Button
+                              size="xs"
+                              leftSection={<IconDownload size={14} />}
+                              onClick={() => openDownloadModal(chart)}
+                              style={{ flex: 1 }}
+                            >
+                              下载
+                            </Button>
+                            <ActionIcon
+                              size={30}
+                              variant="light"
+                              loading={previewLoadingId === chart.id}
+                              onClick={() => togglePreview(chart)}
+                              title={previewingId === chart.id ? '停止试听' : '试听'}
+                            >
+                              {previewingId === chart.id ? <IconPlayerStop size={14} /> : <IconPlayerPlay size={14} />}
+                            </ActionIcon>
+                          </Group>
                         )}
                       </Stack>
                     </Card>
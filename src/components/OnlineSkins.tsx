@@ -4,11 +4,22 @@ import { notifications } from '@mantine/notifications';
 import { IconDownload, IconSearch, IconBrandGithub } from '@tabler/icons-react';
 import { invoke } from '@tauri-apps/api/core';
 import { usePathContext } from '../contexts';
+import { formatError } from '../utils/error';
 
 interface GithubSkin {
   name: string;
   download_url: string;
   size: number;
+  sha?: string | null;
+  source: string;
+}
+
+interface SkinValidation {
+  valid: boolean;
+  missing: string[];
+  unexpected: string[];
+  invalid: { file: string; reason: string }[];
+  hint?: string | null;
 }
 
 interface OnlineSkinsProps {
@@ -29,11 +40,6 @@ export function OnlineSkins({ onRefresh }: OnlineSkinsProps) {
     loadSkins();
   }, []);
 
-  const getProxy = (): string | null => {
-    const savedProxy = localStorage.getItem('httpProxy');
-    return savedProxy && savedProxy.trim() !== '' ? savedProxy : null;
-  };
-
   useEffect(() => {
     if (search.trim() === '') {
       setFilteredSkins(skins);
@@ -48,9 +54,8 @@ export function OnlineSkins({ onRefresh }: OnlineSkinsProps) {
   const loadSkins = async () => {
     setLoading(true);
     try {
-      const skinList = await invoke<GithubSkin[]>('fetch_github_skins', {
-        proxy: getProxy(),
-      });
+      // 皮肤来源和代理由后端设置决定
+      const skinList = await invoke<GithubSkin[]>('fetch_github_skins');
       
       // 过滤出 .zip 文件
       const zipSkins = skinList.filter(skin => skin.name.toLowerCase().endsWith('.zip'));
@@ -80,13 +85,8 @@ export function OnlineSkins({ onRefresh }: OnlineSkinsProps) {
 
     setDownloading(true);
     try {
-      const skinsPath = `${defaultGameFolderPath}\\Skins`;
-
-      await invoke('download_skin_zip', {
-        url: selectedSkin.download_url,
-        skinName: selectedSkin.name,
-        skinsDir: skinsPath,
-        proxy: getProxy(),
+      const validation = await invoke<SkinValidation>('download_skin_zip', {
+        skin: selectedSkin,
       });
 
       notifications.show({
@@ -95,13 +95,27 @@ export function OnlineSkins({ onRefresh }: OnlineSkinsProps) {
         color: 'green',
       });
 
+      if (!validation.valid) {
+        const details = [
+          validation.hint,
+          validation.missing.length > 0 ? `缺失: ${validation.missing.join(', ')}` : null,
+          validation.invalid.length > 0 ? `无效: ${validation.invalid.map(i => `${i.file} (${i.reason})`).join(', ')}` : null,
+        ].filter(Boolean).join('\n');
+        notifications.show({
+          title: '皮肤结构可能有问题',
+          message: details,
+          color: 'yellow',
+          autoClose: false,
+        });
+      }
+
       setDownloadModalOpen(false);
       onRefresh?.();
     } catch (error) {
       console.error('下载皮肤失败:', error);
       notifications.show({
         title: '错误',
-        message: '下载皮肤失败: ' + String(error),
+        message: '下载皮肤失败: ' + formatError(error),
         color: 'red',
       });
     } finally {
@@ -139,19 +153,19 @@ export function OnlineSkins({ onRefresh }: OnlineSkinsProps) {
           ) : (
             <>
               <Text size="sm" c="dimmed">
-                <IconBrandGithub size={14} style={{ display: 'inline', verticalAlign: 'middle' }} /> 来自 GitHub: teamMajdata/MajdataPlay-Skins
+                <IconBrandGithub size={14} style={{ display: 'inline', verticalAlign: 'middle' }} /> 来源: {[...new Set(skins.map(s => s.source))].join(', ') || 'teamMajdata/MajdataPlay-Skins'}
               </Text>
 
               <Grid gutter="sm">
                 {filteredSkins.map((skin) => (
-                  <Grid.Col key={skin.name} span={{ base: 12, sm: 6, md: 4, lg: 3 }}>
+                  <Grid.Col key={`${skin.source}/${skin.name}`} span={{ base: 12, sm: 6, md: 4, lg: 3 }}>
                     <Card shadow="sm" padding="md" radius="md" withBorder>
                       <Stack gap="xs">
                         <Text fw={500} size="sm" lineClamp={1}>
                           {skin.name.replace('.zip', '')}
                         </Text>
                         <Text size="xs" c="dimmed">
-                          大小: {formatFileSize(skin.size)}
+                          大小: {formatFileSize(skin.size)} · {skin.source}
                         </Text>
                         <Button
                           fullWidth
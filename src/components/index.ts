@@ -4,3 +4,5 @@ export { LocalCharts } from './LocalCharts';
 export { OnlineCharts } from './OnlineCharts';
 export { LocalSkins } from './LocalSkins';
 export { OnlineSkins } from './OnlineSkins';
+export { LaunchProfileModal } from './LaunchProfileModal';
+export type { LaunchProfile } from './LaunchProfileModal';
@@ -1,6 +1,7 @@
-import React, { createContext, useContext, useEffect, useState, ReactNode } from 'react';
-import { appDataDir, appLocalDataDir, appCacheDir, resourceDir, join } from '@tauri-apps/api/path';
+import React, { createContext, useCallback, useContext, useEffect, useState, ReactNode } from 'react';
+import { appDataDir, appLocalDataDir, appCacheDir, resourceDir } from '@tauri-apps/api/path';
 import { invoke } from '@tauri-apps/api/core';
+import { getGamePaths, migrateLegacySettings } from '../utils/settings';
 
 interface PathContextType {
   appExePath: string | null;
@@ -12,6 +13,8 @@ interface PathContextType {
   resourcePath: string | null;
   isLoading: boolean;
   error: string | null;
+  // 设置中的游戏目录修改后重新获取
+  refreshGamePaths: () => Promise<void>;
 }
 
 const PathContext = createContext<PathContextType | undefined>(undefined);
@@ -39,6 +42,11 @@ export const PathProvider: React.FC<PathProviderProps> = ({ children }) => {
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
+  const refreshGamePaths = useCallback(async () => {
+    const paths = await getGamePaths();
+    setDefaultGameFolderPath(paths.game_dir);
+  }, []);
+
   useEffect(() => {
     const loadPaths = async () => {
       try {
@@ -68,15 +76,21 @@ export const PathProvider: React.FC<PathProviderProps> = ({ children }) => {
           const exeFolderPath = await invoke<string>('get_app_exe_folder_path');
           setAppExePath(exePath);
           setAppExeFolderPath(exeFolderPath);
-          setDefaultGameFolderPath(await join(exeFolderPath, 'game'));
         } catch (err) {
           console.warn('无法获取exe路径:', err);
           // 如果未实现该命令，使用resource目录作为备选
           setAppExePath(resource);
           setAppExeFolderPath(resource);
-          setDefaultGameFolderPath(await join(resource, 'game'));
         }
 
+        // 游戏目录来自后端设置，首次运行时先导入旧版本保存在 localStorage 中的设置
+        try {
+          await migrateLegacySettings();
+        } catch (err) {
+          console.warn('导入旧版设置失败:', err);
+        }
+        await refreshGamePaths();
+
         setError(null);
       } catch (err) {
         console.error('加载路径失败:', err);
@@ -87,7 +101,7 @@ export const PathProvider: React.FC<PathProviderProps> = ({ children }) => {
     };
 
     loadPaths();
-  }, []);
+  }, [refreshGamePaths]);
 
   const value: PathContextType = {
     appExePath,
@@ -99,6 +113,7 @@ export const PathProvider: React.FC<PathProviderProps> = ({ children }) => {
     defaultGameFolderPath,
     isLoading,
     error,
+    refreshGamePaths,
   };
 
   return <PathContext.Provider value={value}>{children}</PathContext.Provider>;
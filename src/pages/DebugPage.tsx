@@ -1,9 +1,30 @@
-import { Container, Title, Text, Card, Stack, Loader, Code, CopyButton, Tooltip, ActionIcon, Group, Button, TextInput } from '@mantine/core';
-import { IconCopy, IconCheck, IconHash, IconDeviceFloppy } from '@tabler/icons-react';
+import { Container, Title, Text, Card, Stack, Loader, Code, CopyButton, Tooltip, ActionIcon, Group, Button, TextInput, Select } from '@mantine/core';
+import { IconCopy, IconCheck, IconHash, IconDeviceFloppy, IconFileText, IconPlayerPause, IconPlayerPlay, IconTrash } from '@tabler/icons-react';
 import { usePathContext } from '../contexts';
 import { calculateChecksums, saveChecksumsToFile } from '../utils/hash';
-import { useState } from 'react';
+import { useState, useEffect, useRef } from 'react';
 import { notifications } from '@mantine/notifications';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+interface LogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  location: string | null;
+  message: string;
+}
+
+const LOG_LEVEL_OPTIONS = [
+  { value: 'error', label: 'ERROR' },
+  { value: 'warn', label: 'WARN 及以上' },
+  { value: 'info', label: 'INFO 及以上' },
+  { value: 'debug', label: 'DEBUG 及以上' },
+  { value: 'trace', label: '全部' },
+];
+
+const LIVE_LOG_LIMIT = 1000;
+const LEVEL_RANK: Record<string, number> = { ERROR: 0, WARN: 1, INFO: 2, DEBUG: 3, TRACE: 4 };
 
 export function DebugPage() {
   const { appExePath, appDataPath, appLocalDataPath, appCachePath, resourcePath, isLoading, error } = usePathContext();
@@ -11,6 +32,77 @@ export function DebugPage() {
   const [outputFile, setOutputFile] = useState('hashes.json');
   const [hashLoading, setHashLoading] = useState(false);
   const [hashResult, setHashResult] = useState<any>(null);
+  const [logLevel, setLogLevel] = useState<string | null>('info');
+  const [logLimit, setLogLimit] = useState('200');
+  const [logs, setLogs] = useState<LogEntry[] | null>(null);
+  const [logsLoading, setLogsLoading] = useState(false);
+
+  const [backendLevel, setBackendLevel] = useState('');
+  const [liveLogs, setLiveLogs] = useState<LogEntry[]>([]);
+  const [livePaused, setLivePaused] = useState(false);
+  const [liveLevel, setLiveLevel] = useState<string | null>('trace');
+  const [liveKeyword, setLiveKeyword] = useState('');
+  const livePausedRef = useRef(livePaused);
+  livePausedRef.current = livePaused;
+
+  // 实时接收后端日志
+  useEffect(() => {
+    invoke<string | null>('get_log_level').then((level) => setBackendLevel(level || '')).catch(console.error);
+    invoke('set_log_stream', { enabled: true }).catch(console.error);
+    const unlisten = listen<LogEntry>('backend-log', (event) => {
+      if (livePausedRef.current) return;
+      setLiveLogs((prev) => [...prev, event.payload].slice(-LIVE_LOG_LIMIT));
+    });
+    return () => {
+      invoke('set_log_stream', { enabled: false }).catch(console.error);
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleSetBackendLevel = async () => {
+    try {
+      const applied = await invoke<string>('set_log_level', { level: backendLevel || 'info' });
+      setBackendLevel(applied);
+      notifications.show({
+        title: '成功',
+        message: `日志级别已修改为: ${applied}`,
+        color: 'green',
+      });
+    } catch (err: any) {
+      notifications.show({
+        title: '修改失败',
+        message: err.toString(),
+        color: 'red',
+      });
+    }
+  };
+
+  const visibleLiveLogs = liveLogs.filter((entry) => {
+    const maxRank = LEVEL_RANK[(liveLevel || 'trace').toUpperCase()] ?? 4;
+    if ((LEVEL_RANK[entry.level] ?? 4) > maxRank) return false;
+    if (!liveKeyword) return true;
+    const keyword = liveKeyword.toLowerCase();
+    return entry.message.toLowerCase().includes(keyword) || entry.target.toLowerCase().includes(keyword);
+  });
+
+  const handleLoadLogs = async () => {
+    setLogsLoading(true);
+    try {
+      const entries = await invoke<LogEntry[]>('get_recent_logs', {
+        level: logLevel,
+        limit: parseInt(logLimit, 10) || 200,
+      });
+      setLogs(entries);
+    } catch (err: any) {
+      notifications.show({
+        title: '读取日志失败',
+        message: err.toString(),
+        color: 'red',
+      });
+    } finally {
+      setLogsLoading(false);
+    }
+  };
 
   const handleCalculateChecksums = async () => {
     if (!directory) {
@@ -166,6 +258,99 @@ export function DebugPage() {
           )}
         </Stack>
       </Card>
+
+      <Card shadow="sm" padding="lg" radius="md" withBorder mt="xl">
+        <Title order={3} mb="md">
+          实时日志
+        </Title>
+
+        <Stack gap="md">
+          <Group align="flex-end">
+            <TextInput
+              label="后端日志级别"
+              description="例如 debug 或 info,majdata_hub_lib=trace"
+              value={backendLevel}
+              onChange={(e) => setBackendLevel(e.currentTarget.value)}
+              style={{ flex: 1 }}
+            />
+            <Button onClick={handleSetBackendLevel}>应用</Button>
+          </Group>
+
+          <Group align="flex-end">
+            <Select
+              label="显示级别"
+              data={LOG_LEVEL_OPTIONS}
+              value={liveLevel}
+              onChange={setLiveLevel}
+            />
+            <TextInput
+              label="关键字"
+              value={liveKeyword}
+              onChange={(e) => setLiveKeyword(e.currentTarget.value)}
+              style={{ flex: 1 }}
+            />
+            <Button
+              variant="light"
+              leftSection={livePaused ? <IconPlayerPlay size={18} /> : <IconPlayerPause size={18} />}
+              onClick={() => setLivePaused(!livePaused)}
+            >
+              {livePaused ? '继续' : '暂停'}
+            </Button>
+            <Button
+              variant="light"
+              color="gray"
+              leftSection={<IconTrash size={18} />}
+              onClick={() => setLiveLogs([])}
+            >
+              清空
+            </Button>
+          </Group>
+
+          <Code block mah={400} style={{ overflow: 'auto' }}>
+            {visibleLiveLogs.length === 0
+              ? '暂无日志'
+              : visibleLiveLogs.map((entry) => `${entry.timestamp} ${entry.level.padStart(5)} ${entry.target}: ${entry.message}`).join('\n')}
+          </Code>
+        </Stack>
+      </Card>
+
+      <Card shadow="sm" padding="lg" radius="md" withBorder mt="xl">
+        <Title order={3} mb="md">
+          最近日志
+        </Title>
+
+        <Stack gap="md">
+          <Group align="flex-end">
+            <Select
+              label="最低级别"
+              data={LOG_LEVEL_OPTIONS}
+              value={logLevel}
+              onChange={setLogLevel}
+            />
+            <TextInput
+              label="条数"
+              value={logLimit}
+              onChange={(e) => setLogLimit(e.currentTarget.value)}
+              w={100}
+            />
+            <Button
+              leftSection={<IconFileText size={18} />}
+              onClick={handleLoadLogs}
+              loading={logsLoading}
+            >
+              读取日志
+            </Button>
+          </Group>
+
+          {logs && (
+            <Code block mah={400} style={{ overflow: 'auto' }}>
+              {logs.length === 0
+                ? '没有符合条件的日志'
+                : logs.map((entry) => `${entry.timestamp} ${entry.level.padStart(5)} ${entry.target}: ${entry.message}`).join('\n')}
+            </Code>
+          )}
+        </Stack>
+      </Card>
     </Container>
   );
 }
@@ -1,21 +1,39 @@
 import { useState, useEffect } from 'react';
-import { Container, Title, Text, Button, Card, Progress, Alert, List, LoadingOverlay, Select } from '@mantine/core';
+import { Container, Title, Text, Button, Card, Progress, Alert, List, LoadingOverlay, Select, Group, ActionIcon } from '@mantine/core';
 import { notifications } from '@mantine/notifications';
-import { IconDownload, IconRefresh, IconCheck, IconAlertCircle, IconPlayerPlay } from '@tabler/icons-react';
+import { IconDownload, IconRefresh, IconCheck, IconAlertCircle, IconPlayerPlay, IconPlus, IconPencil, IconTrash, IconRestore, IconFileZip } from '@tabler/icons-react';
+import { ask } from '@tauri-apps/plugin-dialog';
+import { LaunchProfileModal, LaunchProfile } from '../components';
 import { usePathContext, useDownloadContext } from '../contexts';
+import { formatError, isGameRunningError } from '../utils/error';
 import { calculateChecksums, FileChecksum } from '../utils/hash';
 import { normalizePath } from '../types';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { join } from '@tauri-apps/api/path';
 
 const REMOTE_ZIP_URL = 'https://github.com/TeamMajdata/MajdataPlay_Build/archive/refs/heads/master.zip';
 const REMOTE_HASH_URL = 'https://github.com/TeamMajdata/MajdataPlay_Build/raw/refs/heads/master/smallest_hashes.json';
 const GITHUB_RAW_BASE = 'https://github.com/TeamMajdata/MajdataPlay_Build/raw/refs/heads/master/';
 
-interface LaunchOption {
-  id: string;
-  label: string;
-  description: string;
+interface RunningGame {
+  pid: number;
+  profile_id: string;
+  game_dir: string;
+  started_at: string;
+  log_path?: string | null;
+}
+
+interface GameLogReport {
+  zip_path: string;
+  files: { path: string; archive_name: string; size: number }[];
+  exceptions: { source: string; line: number; message: string; stack: string[]; count: number }[];
+}
+
+interface GameExit extends RunningGame {
+  exit_code: number | null;
+  success: boolean;
+  duration_secs: number;
 }
 
 export function GamePage() {
@@ -23,9 +41,41 @@ export function GamePage() {
   const { isDownloading, downloadProgress, setIsDownloading, setDownloadProgress, isUpdating, updateList, setIsUpdating, setUpdateList } = useDownloadContext();
   const [hasGameExe, setHasGameExe] = useState(false);
   const [isChecking, setIsChecking] = useState(true);
-  const [launchOptions, setLaunchOptions] = useState<LaunchOption[]>([]);
+  const [launchOptions, setLaunchOptions] = useState<LaunchProfile[]>([]);
+  const [profileModalOpen, setProfileModalOpen] = useState(false);
+  const [editingProfile, setEditingProfile] = useState<LaunchProfile | null>(null);
   const [selectedOption, setSelectedOption] = useState<string | null>(null);
   const [isLaunching, setIsLaunching] = useState(false);
+  const [runningGames, setRunningGames] = useState<RunningGame[]>([]);
+  const [isCollectingLogs, setIsCollectingLogs] = useState(false);
+  const isGameRunning = runningGames.length > 0;
+
+  // 跟踪游戏进程：初始化时获取已在运行的进程，之后通过事件更新
+  useEffect(() => {
+    invoke<RunningGame[]>('get_running_games').then(setRunningGames).catch(console.error);
+
+    const unlistenStarted = listen<RunningGame>('game-started', (event) => {
+      setRunningGames(prev => [...prev.filter(g => g.pid !== event.payload.pid), event.payload]);
+    });
+    const unlistenExited = listen<GameExit>('game-exited', (event) => {
+      const exit = event.payload;
+      setRunningGames(prev => prev.filter(g => g.pid !== exit.pid));
+      if (!exit.success) {
+        notifications.show({
+          title: '游戏异常退出',
+          message: `退出码: ${exit.exit_code ?? '未知'}` + (exit.log_path ? `，输出记录: ${exit.log_path}` : ''),
+          color: 'red',
+          icon: <IconAlertCircle />,
+          autoClose: false,
+        });
+      }
+    });
+
+    return () => {
+      unlistenStarted.then(fn => fn());
+      unlistenExited.then(fn => fn());
+    };
+  }, []);
 
   useEffect(() => {
     const checkLocalHash = async () => {
@@ -59,7 +109,7 @@ export function GamePage() {
   useEffect(() => {
     const loadLaunchOptions = async () => {
       try {
-        const options = await invoke<LaunchOption[]>('get_launch_options');
+        const options = await invoke<LaunchProfile[]>('get_launch_options');
         setLaunchOptions(options);
         if (options.length > 0) {
           setSelectedOption(options[0].id);
@@ -80,11 +130,8 @@ export function GamePage() {
       
       const localHashes = await calculateChecksums(defaultGameFolderPath);
       
-      const httpProxy = localStorage.getItem('httpProxy') || null;
-      
       const remoteHashes: FileChecksum[] = await invoke('fetch_remote_hashes', {
         url: REMOTE_HASH_URL,
-        proxy: httpProxy,
       });
 
       const filesToUpdate: FileChecksum[] = [];
@@ -164,15 +211,11 @@ export function GamePage() {
       
       setDownloadProgress(30);
       
-      // 从 localStorage 获取代理设置
-      const httpProxy = localStorage.getItem('httpProxy') || null;
-      
-      // 使用 Rust 命令下载并解压
+      // 使用 Rust 命令下载并解压（代理由后端设置决定）
       await invoke('download_and_extract', {
         url: REMOTE_ZIP_URL,
         targetPath: defaultGameFolderPath,
         zipPath: zipPath,
-        proxy: httpProxy,
       });
 
       setDownloadProgress(100);
@@ -194,7 +237,7 @@ export function GamePage() {
       notifications.update({
         id: 'downloading',
         title: '下载失败',
-        message: '下载游戏文件时出错: ' + (error as Error).message,
+        message: '下载游戏文件时出错: ' + formatError(error),
         color: 'red',
         icon: <IconAlertCircle />,
         autoClose: 5000,
@@ -222,9 +265,6 @@ export function GamePage() {
         loading: true,
       });
 
-      // 从 localStorage 获取代理设置
-      const httpProxy = localStorage.getItem('httpProxy') || null;
-
       // 批量下载文件（使用 Promise.all 实现并发下载）
       const downloadTasks = updateList.map(async (file) => {
         const fileUrl = GITHUB_RAW_BASE + file.filePath;
@@ -233,7 +273,6 @@ export function GamePage() {
             url: fileUrl,
             filePath: file.filePath,
             targetDir: defaultGameFolderPath,
-            proxy: httpProxy,
           });
           return { success: true, file: file.filePath };
         } catch (error) {
@@ -258,7 +297,18 @@ export function GamePage() {
       }
 
       const failed = results.filter(r => !r.success);
-      if (failed.length > 0) {
+      const gameRunning = failed.find(r => isGameRunningError(r.error));
+      if (gameRunning) {
+        notifications.update({
+          id: 'updating',
+          title: '无法更新',
+          message: formatError(gameRunning.error),
+          color: 'orange',
+          icon: <IconAlertCircle />,
+          autoClose: 5000,
+          loading: false,
+        });
+      } else if (failed.length > 0) {
         notifications.update({
           id: 'updating',
           title: '部分更新失败',
@@ -287,7 +337,7 @@ export function GamePage() {
       notifications.update({
         id: 'updating',
         title: '更新失败',
-        message: '更新文件时出错: ' + (error as Error).message,
+        message: '更新文件时出错: ' + formatError(error),
         color: 'red',
         icon: <IconAlertCircle />,
         autoClose: 5000,
@@ -298,6 +348,38 @@ export function GamePage() {
     }
   };
 
+  // 启动配置增删改
+  const handleProfilesSaved = (profiles: LaunchProfile[], savedId: string) => {
+    setLaunchOptions(profiles);
+    setSelectedOption(savedId);
+  };
+
+  const handleDeleteProfile = async () => {
+    const option = launchOptions.find(opt => opt.id === selectedOption);
+    if (!option) return;
+    const confirmed = await ask(`确定要删除启动配置 "${option.label}" 吗？`, { title: '确认删除', kind: 'warning' });
+    if (!confirmed) return;
+    try {
+      const profiles = await invoke<LaunchProfile[]>('delete_launch_profile', { id: option.id });
+      setLaunchOptions(profiles);
+      setSelectedOption(profiles[0]?.id ?? null);
+    } catch (error) {
+      notifications.show({ title: '错误', message: '删除启动配置失败: ' + String(error), color: 'red' });
+    }
+  };
+
+  const handleResetProfiles = async () => {
+    const confirmed = await ask('确定要恢复内置启动配置吗？自定义配置将被清除。', { title: '确认恢复', kind: 'warning' });
+    if (!confirmed) return;
+    try {
+      const profiles = await invoke<LaunchProfile[]>('reset_launch_profiles');
+      setLaunchOptions(profiles);
+      setSelectedOption(profiles[0]?.id ?? null);
+    } catch (error) {
+      notifications.show({ title: '错误', message: '恢复启动配置失败: ' + String(error), color: 'red' });
+    }
+  };
+
   // 启动游戏
   const handleLaunchGame = async () => {
     if (!defaultGameFolderPath || !selectedOption) {
@@ -313,8 +395,8 @@ export function GamePage() {
     try {
       setIsLaunching(true);
       
+      // 游戏目录和运行器由后端设置决定
       await invoke('launch_game', {
-        gameDir: defaultGameFolderPath,
         optionId: selectedOption,
       });
 
@@ -329,7 +411,7 @@ export function GamePage() {
       console.error('启动游戏失败:', error);
       notifications.show({
         title: '启动失败',
-        message: '启动游戏时出错: ' + (error as Error).message,
+        message: '启动游戏时出错: ' + formatError(error),
         color: 'red',
         icon: <IconAlertCircle />,
       });
@@ -338,6 +420,36 @@ export function GamePage() {
     }
   };
 
+  // 收集游戏日志用于反馈问题
+  const handleCollectLogs = async () => {
+    if (!defaultGameFolderPath) return;
+
+    try {
+      setIsCollectingLogs(true);
+      const report = await invoke<GameLogReport>('collect_game_logs');
+      const exceptionText = report.exceptions.length > 0
+        ? `，发现 ${report.exceptions.length} 个异常: ${report.exceptions[0].message}`
+        : '，未发现异常';
+      notifications.show({
+        title: '日志已打包',
+        message: `共 ${report.files.length} 个日志${exceptionText}\n${report.zip_path}`,
+        color: report.exceptions.length > 0 ? 'yellow' : 'green',
+        icon: <IconFileZip />,
+        autoClose: false,
+      });
+    } catch (error) {
+      console.error('收集日志失败:', error);
+      notifications.show({
+        title: '收集日志失败',
+        message: formatError(error),
+        color: 'red',
+        icon: <IconAlertCircle />,
+      });
+    } finally {
+      setIsCollectingLogs(false);
+    }
+  };
+
   return (
     <Container size="xl" py="xl">
       <div>
@@ -418,12 +530,15 @@ export function GamePage() {
                 leftSection={<IconRefresh size={20} />}
                 onClick={handleUpdate}
                 loading={isUpdating}
-                disabled={updateList.length === 0}
+                disabled={updateList.length === 0 || isGameRunning}
                 className="flex-1"
               >
                 更新游戏
               </Button>
             </div>
+            {isGameRunning && (
+              <Text size="sm" c="orange" mt="sm">游戏正在运行，关闭游戏后才能更新</Text>
+            )}
           </div>
         )}
       </Card>
@@ -435,17 +550,44 @@ export function GamePage() {
             启动游戏
           </Title>
           
-          <Select
-            label="选择启动方式"
-            placeholder="选择一个启动项"
-            description="选择不同的图形 API 或游戏模式"
-            data={launchOptions.map(opt => ({
-              value: opt.id,
-              label: opt.label,
-            }))}
-            value={selectedOption}
-            onChange={setSelectedOption}
-            mb="md"
+          <Group align="flex-end" gap="xs" mb="md">
+            <Select
+              label="选择启动方式"
+              placeholder="选择一个启动项"
+              description="选择不同的图形 API 或游戏模式"
+              data={launchOptions.map(opt => ({
+                value: opt.id,
+                label: opt.label,
+              }))}
+              value={selectedOption}
+              onChange={setSelectedOption}
+              style={{ flex: 1 }}
+            />
+            <ActionIcon size="lg" variant="light" title="新建启动配置" onClick={() => { setEditingProfile(null); setProfileModalOpen(true); }}>
+              <IconPlus size={18} />
+            </ActionIcon>
+            <ActionIcon
+              size="lg"
+              variant="light"
+              title="编辑启动配置"
+              disabled={!selectedOption}
+              onClick={() => { setEditingProfile(launchOptions.find(opt => opt.id === selectedOption) ?? null); setProfileModalOpen(true); }}
+            >
+              <IconPencil size={18} />
+            </ActionIcon>
+            <ActionIcon size="lg" variant="light" color="red" title="删除启动配置" disabled={!selectedOption} onClick={handleDeleteProfile}>
+              <IconTrash size={18} />
+            </ActionIcon>
+            <ActionIcon size="lg" variant="light" color="gray" title="恢复内置启动配置" onClick={handleResetProfiles}>
+              <IconRestore size={18} />
+            </ActionIcon>
+          </Group>
+
+          <LaunchProfileModal
+            opened={profileModalOpen}
+            profile={editingProfile}
+            onClose={() => setProfileModalOpen(false)}
+            onSaved={handleProfilesSaved}
           />
 
           {selectedOption && (
@@ -459,10 +601,22 @@ export function GamePage() {
             leftSection={<IconPlayerPlay size={20} />}
             onClick={handleLaunchGame}
             loading={isLaunching}
-            disabled={!selectedOption || launchOptions.length === 0}
+            disabled={!selectedOption || launchOptions.length === 0 || isGameRunning}
             fullWidth
           >
-            启动游戏
+            {isGameRunning ? `游戏运行中 (PID ${runningGames[0].pid})` : '启动游戏'}
+          </Button>
+
+          <Button
+            mt="sm"
+            variant="light"
+            color="gray"
+            leftSection={<IconFileZip size={18} />}
+            onClick={handleCollectLogs}
+            loading={isCollectingLogs}
+            fullWidth
+          >
+            收集游戏日志
           </Button>
         </Card>
       )}
@@ -1,12 +1,31 @@
 import { useState, useEffect } from 'react';
-import { Container, Title, Text, Card, TextInput, Button, Group, Stack, ActionIcon } from '@mantine/core';
-import { IconFolder, IconDeviceFloppy, IconFolderOpen, IconNetwork } from '@tabler/icons-react';
+import { Container, Title, Text, Card, TextInput, Button, Group, Stack, ActionIcon, Select, Textarea, NumberInput, List, Code } from '@mantine/core';
+import { IconFolder, IconDeviceFloppy, IconFolderOpen, IconNetwork, IconTerminal2, IconWorld, IconClock } from '@tabler/icons-react';
 import { notifications } from '@mantine/notifications';
 import { open } from '@tauri-apps/plugin-dialog';
 import { usePathContext } from '../contexts';
+import { formatError } from '../utils/error';
+import { AppSettings, LaunchRunnerConfig, getSettings, saveSettings } from '../utils/settings';
+
+const RUNNER_TYPES = [
+  { value: 'default', label: '自动（Windows 直接运行，其他平台使用 wine）' },
+  { value: 'native', label: '直接运行' },
+  { value: 'wine', label: 'Wine' },
+  { value: 'proton', label: 'Proton' },
+  { value: 'custom', label: '自定义命令' },
+];
+
+const parseEnv = (text: string): Record<string, string> =>
+  Object.fromEntries(
+    text
+      .split('\n')
+      .map(line => line.trim())
+      .filter(line => line && line.includes('='))
+      .map(line => [line.slice(0, line.indexOf('=')).trim(), line.slice(line.indexOf('=') + 1)])
+  );
 
 export function SettingPage() {
-  const { appExeFolderPath, defaultGameFolderPath } = usePathContext();
+  const { appExeFolderPath, defaultGameFolderPath, refreshGamePaths } = usePathContext();
   if (!appExeFolderPath || !defaultGameFolderPath) {
     return (
       <Container size="xl" py="xl">
@@ -14,18 +33,43 @@ export function SettingPage() {
       </Container>
     );
   }
-  const [gamePath, setGamePath] = useState<string>(defaultGameFolderPath);
+  const [settings, setSettings] = useState<AppSettings | null>(null);
+  const [gamePath, setGamePath] = useState<string>('');
   const [httpProxy, setHttpProxy] = useState<string>('');
+  const [requestTimeout, setRequestTimeout] = useState<number>(300);
+  const [apiBase, setApiBase] = useState<string>('');
+  const [skinSources, setSkinSources] = useState<string>('');
+  const [runnerType, setRunnerType] = useState<string>('default');
+  const [runnerPath, setRunnerPath] = useState<string>('');
+  const [runnerPrefix, setRunnerPrefix] = useState<string>('');
+  const [runnerTemplate, setRunnerTemplate] = useState<string>('');
+  const [runnerEnv, setRunnerEnv] = useState<string>('');
+
+  const applySettings = (loaded: AppSettings) => {
+    setSettings(loaded);
+    setGamePath(loaded.game_dir ?? '');
+    setHttpProxy(loaded.proxy ?? '');
+    setRequestTimeout(loaded.request_timeout_secs);
+    setApiBase(loaded.api_base ?? '');
+    setSkinSources(loaded.skin_sources.length > 0 ? JSON.stringify(loaded.skin_sources, null, 2) : '');
+    const runner = loaded.runner;
+    setRunnerType(runner?.type ?? 'default');
+    setRunnerPath(runner?.wine ?? runner?.proton ?? '');
+    setRunnerPrefix(runner?.prefix ?? '');
+    setRunnerTemplate(runner?.template ?? '');
+    setRunnerEnv(Object.entries(runner?.env ?? {}).map(([k, v]) => `${k}=${v}`).join('\n'));
+  };
 
   useEffect(() => {
-    const savedPath = localStorage.getItem('gamePath');
-    if (savedPath) {
-      setGamePath(savedPath);
-    }
-    const savedProxy = localStorage.getItem('httpProxy');
-    if (savedProxy) {
-      setHttpProxy(savedProxy);
-    }
+    getSettings()
+      .then(applySettings)
+      .catch((error) => {
+        notifications.show({
+          title: '错误',
+          message: '读取设置失败: ' + formatError(error),
+          color: 'red',
+        });
+      });
   }, []);
 
   const handleSelectFolder = async () => {
@@ -48,14 +92,65 @@ export function SettingPage() {
     }
   };
 
-  const handleSave = () => {
-    localStorage.setItem('gamePath', gamePath);
-    localStorage.setItem('httpProxy', httpProxy);
-    notifications.show({
-      title: '保存成功',
-      message: '设置已保存',
-      color: 'green',
-    });
+  const handleSave = async () => {
+    if (!settings) return;
+
+    let sources: unknown[] = [];
+    if (skinSources.trim()) {
+      try {
+        sources = JSON.parse(skinSources);
+        if (!Array.isArray(sources)) throw new Error('皮肤来源必须是数组');
+      } catch (error) {
+        notifications.show({
+          title: '错误',
+          message: '皮肤来源格式错误: ' + formatError(error),
+          color: 'red',
+        });
+        return;
+      }
+    }
+
+    let runner: LaunchRunnerConfig | null = null;
+    if (runnerType !== 'default') {
+      runner = {
+        type: runnerType as LaunchRunnerConfig['type'],
+        env: parseEnv(runnerEnv),
+      };
+      if (runnerType === 'wine') {
+        runner.wine = runnerPath || undefined;
+        runner.prefix = runnerPrefix || undefined;
+      } else if (runnerType === 'proton') {
+        runner.proton = runnerPath;
+        runner.prefix = runnerPrefix;
+      } else if (runnerType === 'custom') {
+        runner.template = runnerTemplate;
+      }
+    }
+
+    try {
+      const saved = await saveSettings({
+        ...settings,
+        game_dir: gamePath.trim() || null,
+        proxy: httpProxy.trim() || null,
+        request_timeout_secs: requestTimeout,
+        api_base: apiBase.trim() || null,
+        runner,
+        skin_sources: sources,
+      });
+      applySettings(saved);
+      await refreshGamePaths();
+      notifications.show({
+        title: '保存成功',
+        message: '设置已保存',
+        color: 'green',
+      });
+    } catch (error) {
+      notifications.show({
+        title: '保存失败',
+        message: formatError(error),
+        color: 'red',
+      });
+    }
   };
 
   return (
@@ -83,12 +178,12 @@ export function SettingPage() {
                 <IconFolderOpen size={18} />
               </ActionIcon>
             }
-            placeholder="./game"
+            placeholder={defaultGameFolderPath}
             value={gamePath}
             onChange={(event) => setGamePath(event.currentTarget.value)}
             size="md"
             label="游戏目录路径"
-            description="点击右侧图标选择游戏资源所在的文件夹路径"
+            description="点击右侧图标选择游戏资源所在的文件夹路径，留空表示使用程序目录下的 game 文件夹"
           />
 
           <TextInput
@@ -101,6 +196,91 @@ export function SettingPage() {
             description="留空表示不使用代理，支持 http:// 和 https:// 格式"
           />
 
+          <NumberInput
+            leftSection={<IconClock size={18} />}
+            value={requestTimeout}
+            onChange={(value) => setRequestTimeout(typeof value === 'number' ? value : 0)}
+            min={0}
+            size="md"
+            label="请求超时（秒）"
+            description="单个网络请求的最长时间，填 0 表示使用默认值 300 秒"
+          />
+
+          <TextInput
+            leftSection={<IconWorld size={18} />}
+            placeholder="https://majdata.net/api3/api"
+            value={apiBase}
+            onChange={(event) => setApiBase(event.currentTarget.value)}
+            size="md"
+            label="谱面 API 地址"
+            description="留空表示使用官方地址，可填写镜像站"
+          />
+
+          <Select
+            leftSection={<IconTerminal2 size={18} />}
+            data={RUNNER_TYPES}
+            value={runnerType}
+            onChange={(value) => setRunnerType(value || 'default')}
+            size="md"
+            label="游戏运行器"
+            description="在 Linux 上可通过 Wine 或 Proton 启动游戏"
+          />
+
+          {(runnerType === 'wine' || runnerType === 'proton') && (
+            <>
+              <TextInput
+                placeholder={runnerType === 'wine' ? 'wine' : '~/.steam/steam/steamapps/common/Proton 9.0/proton'}
+                value={runnerPath}
+                onChange={(event) => setRunnerPath(event.currentTarget.value)}
+                size="md"
+                label={runnerType === 'wine' ? 'Wine 可执行文件' : 'Proton 脚本路径'}
+                description={runnerType === 'wine' ? '留空表示使用 PATH 中的 wine' : undefined}
+              />
+              <TextInput
+                value={runnerPrefix}
+                onChange={(event) => setRunnerPrefix(event.currentTarget.value)}
+                size="md"
+                label={runnerType === 'wine' ? 'WINEPREFIX' : '兼容数据目录（STEAM_COMPAT_DATA_PATH）'}
+                description={runnerType === 'wine' ? '留空表示使用默认前缀' : undefined}
+              />
+            </>
+          )}
+
+          {runnerType === 'custom' && (
+            <TextInput
+              placeholder="gamemoderun wine {exe} {args}"
+              value={runnerTemplate}
+              onChange={(event) => setRunnerTemplate(event.currentTarget.value)}
+              size="md"
+              label="自定义启动命令"
+              description="支持 {exe}（游戏程序路径）、{args}（启动参数）、{dir}（游戏目录）占位符"
+            />
+          )}
+
+          {runnerType !== 'default' && (
+            <Textarea
+              placeholder={'DXVK_HUD=fps\nWINEDEBUG=-all'}
+              value={runnerEnv}
+              onChange={(event) => setRunnerEnv(event.currentTarget.value)}
+              size="md"
+              label="环境变量"
+              description="每行一个，格式为 KEY=VALUE"
+              autosize
+              minRows={2}
+            />
+          )}
+
+          <Textarea
+            placeholder={'[{ "name": "我的皮肤", "type": "github", "owner": "...", "repo": "..." }]'}
+            value={skinSources}
+            onChange={(event) => setSkinSources(event.currentTarget.value)}
+            size="md"
+            label="皮肤来源"
+            description="JSON 数组，留空表示使用官方皮肤仓库"
+            autosize
+            minRows={2}
+          />
+
           <Group justify="flex-end">
             <Button
               leftSection={<IconDeviceFloppy size={18} />}
@@ -112,6 +292,20 @@ export function SettingPage() {
           </Group>
         </Stack>
       </Card>
+
+      <Card shadow="sm" mt='xl' padding="lg" radius="md" withBorder>
+        <Title order={3} mb="xs">
+          日志
+        </Title>
+        <Text c="dimmed" size="sm">
+          日志默认每天一个文件、保留 7 个，可在启动本工具前通过环境变量调整：
+        </Text>
+        <List size="sm" mt="xs">
+          <List.Item><Code>MAJDATAHUB_LOG_ROTATION</Code>：daily（按天）或 size（按大小）</List.Item>
+          <List.Item><Code>MAJDATAHUB_LOG_MAX_SIZE_MB</Code>：按大小轮转时单个文件的上限，默认 10</List.Item>
+          <List.Item><Code>MAJDATAHUB_LOG_MAX_FILES</Code>：保留的日志文件数，默认 7</List.Item>
+        </List>
+      </Card>
     </Container>
   );
 }
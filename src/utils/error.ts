@@ -0,0 +1,18 @@
+// 游戏运行时修改游戏文件的命令返回的错误
+export interface GameRunningError {
+  kind: 'game_running';
+  pid: number | null;
+  game_dir: string;
+  message: string;
+}
+
+export const isGameRunningError = (error: unknown): error is GameRunningError =>
+  typeof error === 'object' && error !== null && (error as { kind?: string }).kind === 'game_running';
+
+// 将命令错误转换为可显示的文本（命令错误可能是字符串或带 message 的对象）
+export const formatError = (error: unknown): string => {
+  if (typeof error === 'string') return error;
+  if (isGameRunningError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+};
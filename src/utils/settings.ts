@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// 游戏运行器配置（与后端 LaunchRunner 对应）
+export interface LaunchRunnerConfig {
+  type: 'native' | 'wine' | 'proton' | 'custom';
+  wine?: string | null;
+  proton?: string;
+  prefix?: string | null;
+  steam_dir?: string | null;
+  template?: string;
+  env?: Record<string, string>;
+}
+
+// 应用设置（保存在后端应用配置目录的 settings.json 中）
+export interface AppSettings {
+  version: number;
+  game_dir: string | null;
+  proxy: string | null;
+  request_timeout_secs: number;
+  api_base: string | null;
+  runner: LaunchRunnerConfig | null;
+  skin_sources: unknown[];
+}
+
+// 根据设置得到的游戏相关目录
+export interface GamePaths {
+  game_dir: string;
+  maicharts_dir: string;
+  skins_dir: string;
+}
+
+export const getSettings = () => invoke<AppSettings>('get_settings');
+
+export const saveSettings = (settings: AppSettings) =>
+  invoke<AppSettings>('save_settings', { newSettings: settings });
+
+export const getGamePaths = () => invoke<GamePaths>('get_game_paths');
+
+// 旧版本保存在 localStorage 中的设置项
+const LEGACY_KEYS = ['gamePath', 'httpProxy', 'launchRunner', 'skinSources'];
+
+// 将旧版本 localStorage 中的设置导入后端（只执行一次，导入后删除）
+export const migrateLegacySettings = async () => {
+  const legacy = Object.fromEntries(
+    LEGACY_KEYS.map(key => [key, localStorage.getItem(key)]).filter(([, value]) => value !== null)
+  );
+  if (Object.keys(legacy).length === 0) return;
+
+  await invoke<AppSettings>('import_legacy_settings', { legacy });
+  LEGACY_KEYS.forEach(key => localStorage.removeItem(key));
+};